@@ -1,23 +1,38 @@
 #include <memory>
+#include <algorithm>
 #include <QtWidgets/QMessageBox>
+#include <QRegularExpression>
+#include <QMutexLocker>
 #include "AudiobookScan.h"
+#include "Util.h"
 
 // helper functions
 
 // do the actual recusrive directory-element-scan directory
-static void performScanDirectory(QSqlRecord directoryRecord, std::shared_ptr<QDir> currentDirectory, Audiobook* audiobook);
+static void performScanDirectory(QSqlRecord directoryRecord,
+                                 std::shared_ptr<QDir> currentDirectory,
+                                 Audiobook* audiobook,
+                                 bool combineDiscFolders);
 static QMap<QString, bool> isAudioBookFileCache;
 
-void Core::scanDirectory(QSqlRecord directoryRecord, Audiobook* audiobook) {
+// set from the ui thread, read by the scanning threads
+static QMutex extensionOverrideMutex;
+static QStringList extraFileExtensions;
+static QStringList ignoredFileExtensions;
+
+void Core::scanDirectory(QSqlRecord directoryRecord, Audiobook* audiobook, bool combineDiscFolders) {
     QString path = directoryRecord.value("full_path").toString();
     std::shared_ptr<QDir> currentDirectory(new QDir(path));
 
     if(currentDirectory->exists()) {
-        performScanDirectory(directoryRecord, currentDirectory, audiobook);
+        performScanDirectory(directoryRecord, currentDirectory, audiobook, combineDiscFolders);
     }
 }
 
-void performScanDirectory(QSqlRecord directoryRecord, std::shared_ptr<QDir> currentDirectory, Audiobook* audiobook) {
+void performScanDirectory(QSqlRecord directoryRecord,
+                          std::shared_ptr<QDir> currentDirectory,
+                          Audiobook* audiobook,
+                          bool combineDiscFolders) {
     QDirIterator it(*currentDirectory, QDirIterator::NoIteratorFlags);
     std::vector<std::shared_ptr<QDir>> loadedDirectories;
     std::vector<std::shared_ptr<QFile>> loadedAudioFiles;
@@ -50,11 +65,11 @@ void performScanDirectory(QSqlRecord directoryRecord, std::shared_ptr<QDir> curr
         // to register this audiobook right away
 
         // TODO: add more checks here...
-        if(Core::checkDirectorysimilarity(loadedDirectories)) {
+        if(Core::checkDirectorysimilarity(loadedDirectories, combineDiscFolders)) {
             audiobook->registerAudiobook(directoryRecord, currentDirectory);
         } else {
             for(auto &dir : loadedDirectories) {
-                performScanDirectory(directoryRecord, dir, audiobook);
+                performScanDirectory(directoryRecord, dir, audiobook, combineDiscFolders);
             }
         }
     } else {
@@ -64,22 +79,49 @@ void performScanDirectory(QSqlRecord directoryRecord, std::shared_ptr<QDir> curr
     }
 }
 
-bool Core::checkDirectorysimilarity(std::vector<std::shared_ptr<QDir>> dirList) {
+bool Core::checkDirectorysimilarity(std::vector<std::shared_ptr<QDir>> dirList, bool combineDiscFolders) {
     // base case
     if(1 == dirList.size()) {
         return true;
     }
 
+    // every subdirectory is a disc of the same audiobook, files get ordered by disc when they are added
+    if(combineDiscFolders &&
+       std::all_of(dirList.begin(), dirList.end(), [](std::shared_ptr<QDir> dir) {
+           return Core::isDiscDirectory(dir);
+       })) {
+        return true;
+    }
+
     // TODO: implement string similarity checks here
     return false;
 }
 
+bool Core::isDiscDirectory(std::shared_ptr<QDir> directory) {
+    static const QRegularExpression discPattern("(disc|cd|part)\\s*\\d+",
+                                                QRegularExpression::CaseInsensitiveOption);
+
+    return discPattern.match(directory->dirName()).hasMatch();
+}
+
 bool Core::isAudiobookFile(std::shared_ptr<QFile> file, QString path) {
     // by default, non-existing file is not considered to be an audiobook file
     if(!file->exists()) {
         return false;
     }
 
+    // the extension overrides are checked before the cache, so changing them doesn't need a restart
+    {
+        QMutexLocker locker(&extensionOverrideMutex);
+        auto suffix = QFileInfo(*file).suffix().toLower();
+        if(ignoredFileExtensions.contains(suffix)) {
+            return false;
+        }
+        if(extraFileExtensions.contains(suffix)) {
+            return true;
+        }
+    }
+
     // if this is called with a null path, then don't bother
     if(!path.isNull()) {
         if(isAudioBookFileCache.contains(path)) {
@@ -105,6 +147,12 @@ bool Core::isAudiobookFile(std::shared_ptr<QFile> file, QString path) {
 }
 
 
+void Core::setFileExtensionOverrides(QStringList extraExtensions, QStringList ignoredExtensions) {
+    QMutexLocker locker(&extensionOverrideMutex);
+    extraFileExtensions = extraExtensions;
+    ignoredFileExtensions = ignoredExtensions;
+}
+
 QList<QString> Core::getAllFiles(std::shared_ptr<QDir> directory) {
     QList<QString> filePaths;
     QDirIterator it(*directory, QDirIterator::NoIteratorFlags);
@@ -133,7 +181,7 @@ QList<QString> Core::getAllFiles(std::shared_ptr<QDir> directory) {
         }
     }
 
-    filePaths.sort();
+    std::sort(filePaths.begin(), filePaths.end(), Core::naturalLessThan);
 
     return filePaths;
 }
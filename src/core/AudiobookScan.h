@@ -13,13 +13,19 @@
 namespace Core {
 
     // function to directory-element-scan a directory
-    void scanDirectory(QSqlRecord directoryRecord, Audiobook* audiobook);
+    void scanDirectory(QSqlRecord directoryRecord, Audiobook* audiobook, bool combineDiscFolders = false);
 
     bool isAudiobookFile(std::shared_ptr<QFile> file, QString path = QString());
 
+    // extensions that override the file type detection of isAudiobookFile, without the dot
+    void setFileExtensionOverrides(QStringList extraExtensions, QStringList ignoredExtensions);
+
     bool isAudiobookFile(const QFile& file, QString path = QString());
 
     QList<QString> getAllFiles(std::shared_ptr<QDir> directory);
 
-    bool checkDirectorysimilarity(std::vector<std::shared_ptr<QDir>> directoryList);
+    bool checkDirectorysimilarity(std::vector<std::shared_ptr<QDir>> directoryList, bool combineDiscFolders = false);
+
+    // true if the directory looks like one disc of a multi-disc audiobook
+    bool isDiscDirectory(std::shared_ptr<QDir> directory);
 }
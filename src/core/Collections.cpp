@@ -0,0 +1,61 @@
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QVariant>
+#include <QDebug>
+#include "Collections.h"
+
+QList<Core::Collection> Core::getCollections() {
+    QList<Collection> collections;
+
+    QSqlQuery query;
+    if(!query.exec("SELECT id, name FROM collections ORDER BY name COLLATE NOCASE ASC")) {
+        qWarning() << "collection list query failed: " << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return collections;
+    }
+
+    while(query.next()) {
+        Collection collection;
+        collection.id = query.value("id").toInt();
+        collection.name = query.value("name").toString();
+        collections << collection;
+    }
+
+    return collections;
+}
+
+int Core::findOrCreateCollection(QString name) {
+    name = name.trimmed();
+    if(name.isEmpty()) {
+        return -1;
+    }
+
+    QSqlQuery insertQuery;
+    insertQuery.prepare("INSERT OR IGNORE INTO collections (name) VALUES (?)");
+    insertQuery.addBindValue(name);
+    if(!insertQuery.exec()) {
+        qWarning() << "collection insert query failed: " << insertQuery.lastError().driverText()
+                   << ", " << insertQuery.lastError().databaseText();
+        return -1;
+    }
+
+    QSqlQuery query;
+    query.prepare("SELECT id FROM collections WHERE name = ?");
+    query.addBindValue(name);
+    if(!query.exec() || !query.next()) {
+        qWarning() << "collection id query failed: " << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return -1;
+    }
+
+    return query.value("id").toInt();
+}
+
+void Core::removeUnusedCollections() {
+    QSqlQuery query;
+    if(!query.exec("DELETE FROM collections WHERE id NOT IN "
+                   "(SELECT collection_id FROM directories WHERE collection_id IS NOT NULL)")) {
+        qWarning() << "unused collection delete query failed: " << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
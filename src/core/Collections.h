@@ -0,0 +1,25 @@
+#ifndef NODOKANATIVE_COLLECTIONS_H
+#define NODOKANATIVE_COLLECTIONS_H
+
+#include <QString>
+#include <QList>
+
+namespace Core {
+
+    struct Collection {
+        int id;
+        QString name;
+    };
+
+    // every collection, sorted by name
+    QList<Collection> getCollections();
+
+    // the id of the collection with this name, it is created if there is none yet, -1 on failure
+    int findOrCreateCollection(QString name);
+
+    // collections are only kept while a directory belongs to them
+    void removeUnusedCollections();
+}
+
+
+#endif //NODOKANATIVE_COLLECTIONS_H
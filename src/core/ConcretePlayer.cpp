@@ -4,11 +4,33 @@
 
 #include <QDebug>
 #include <future>
+#include <algorithm>
+#include <thread>
+#include <chrono>
 #include <include/vlc/vlc.h>
 #include "ConcretePlayer.h"
+#include "Util.h"
 #include <QTextCodec>
+#include <QElapsedTimer>
+#include <QThread>
 #include <iostream>
 
+// only rewind when the book has been left alone for longer than this
+const static qint64 SMART_RESUME_MIN_GAP_SECS = 10 * 60;
+
+// how many manual seeks can be undone
+const static size_t MAX_SEEK_HISTORY = 20;
+
+// how often loading a file is tried before giving up, the delay grows with each attempt
+const static int LOAD_MEDIA_ATTEMPTS = 3;
+const static int LOAD_MEDIA_RETRY_DELAY_MS = 200;
+
+// how often the volume is changed while fading
+const static int FADE_STEP_MS = 20;
+
+// how often the audio output devices are checked while pausing on a removed device is turned on
+const static int OUTPUT_DEVICE_POLL_MS = 1000;
+
 
 Core::ConcretePlayer::ConcretePlayer(Setting* setting, std::shared_ptr<ProxyManager> manager) {
     // load settings
@@ -20,7 +42,16 @@ Core::ConcretePlayer::ConcretePlayer(Setting* setting, std::shared_ptr<ProxyMana
     this->speed = setting->getSpeed();
 
     /* Load the VLC engine */
-    this->inst = libvlc_new(0, NULL);
+    if(setting->getNormalizeVolume()) {
+        // use the ReplayGain tags when a file has them, and level everything else on the fly
+        const char* const vlcArgs[] = {
+                "--audio-replay-gain-mode=track",
+                "--audio-filter=normvol"
+        };
+        this->inst = libvlc_new(2, vlcArgs);
+    } else {
+        this->inst = libvlc_new(0, NULL);
+    }
     if(this->inst == NULL) {
         qWarning() << "ERROR";
         throw "Exception has occured";
@@ -48,6 +79,31 @@ Core::ConcretePlayer::ConcretePlayer(Setting* setting, std::shared_ptr<ProxyMana
     this->threadPool = std::unique_ptr<ThreadPool>(new ThreadPool(1));
 
     this->hasSeekTo = false;
+    this->isLoadPending = false;
+    this->currentFadeId = 0;
+    this->runningFadeId = 0;
+    this->isFadingIn = false;
+    this->fadeLevel = 1.0;
+    this->fadeStartLevel = 1.0;
+    this->fadeSteps = 0;
+    this->fadeStep = 0;
+    this->fadeTimer = new QTimer(this);
+    this->fadeTimer->setInterval(FADE_STEP_MS);
+    connect(this->fadeTimer, &QTimer::timeout, this, &ConcretePlayer::stepFade);
+    this->tickInterval = setting->getTickInterval();
+    this->isSeeking = false;
+    this->lastTickAt = 0;
+    this->repeatStart = 0;
+    this->repeatEnd = 0;
+    this->hasRepeatStart = false;
+    this->hasRepeatEnd = false;
+
+    this->outputDeviceTimer = new QTimer(this);
+    this->outputDeviceTimer->setInterval(OUTPUT_DEVICE_POLL_MS);
+    connect(this->outputDeviceTimer, &QTimer::timeout, this, &ConcretePlayer::checkOutputDevices);
+    connect(this->setting, &Setting::pauseOnOutputRemovedUpdated,
+            this, &ConcretePlayer::setPauseOnOutputRemoved);
+    this->setPauseOnOutputRemoved(setting->getPauseOnOutputRemoved());
 }
 
 bool Core::ConcretePlayer::canLoadMedia(QSqlRecord record) {
@@ -72,49 +128,191 @@ void Core::ConcretePlayer::loadMedia(QSqlRecord record) {
         return;
     }
 
+    // repeat points and seek history can't span across files
+    this->clearRepeat();
+    this->seekHistory.clear();
+
     this->currentPath = audiobookFileProxy->path();
+    this->loadTimer.start();
 
-    auto path =  this->currentPath;
-    this->currentFile = std::unique_ptr<QFile>(new QFile(path));
+    this->tryLoadMedia(this->currentPath, 1);
+}
 
-    if(!this->currentFile->open(QIODevice::ReadWrite)) {
-        qDebug() << "QFILE FAILED!: " << path;
+void Core::ConcretePlayer::tryLoadMedia(QString path, int attempt) {
+    // another file was loaded while this one was waiting for a retry
+    if(this->mediaLoaded || path != this->currentPath) {
         return;
     }
 
+    if(!this->openMediaItem(path)) {
+        // the file is known to exist, so a failure here is usually transient (e.g. a drive waking up from sleep)
+        if(attempt < LOAD_MEDIA_ATTEMPTS) {
+            this->isLoadPending = true;
+            QMetaObject::invokeMethod(this, "scheduleLoadRetry", Q_ARG(QString, path), Q_ARG(int, attempt));
+            return;
+        }
 
-    this->mediaItem = libvlc_media_new_fd(this->inst, this->currentFile->handle());
-    if(this->mediaItem == NULL) {
+        qWarning() << "Loading media failed after " << LOAD_MEDIA_ATTEMPTS << " attempts: " << path;
+        this->isLoadPending = false;
         return;
     }
+    this->isLoadPending = false;
+
     libvlc_media_player_set_media(this->mediaPlayer, this->mediaItem);
     this->mediaEventManager = libvlc_media_event_manager(this->mediaItem);
 
     this->setupMediaCallbacks();
 
     this->mediaLoaded = true;
+    Core::logTiming("load_media", this->currentPath, this->currentFile->size(), this->loadTimer.elapsed());
 
     if(this->autoPlay) {
         this->play();
     }
 }
 
+void Core::ConcretePlayer::scheduleLoadRetry(QString path, int attempt) {
+    // waiting here instead of sleeping keeps the window responsive while the drive wakes up
+    QTimer::singleShot(LOAD_MEDIA_RETRY_DELAY_MS * attempt, this, [this, path, attempt]() {
+        this->tryLoadMedia(path, attempt + 1);
+    });
+}
+
+bool Core::ConcretePlayer::openMediaItem(QString path) {
+    this->currentFile = std::unique_ptr<QFile>(new QFile(path));
+
+    if(!this->currentFile->open(QIODevice::ReadWrite)) {
+        qDebug() << "QFILE FAILED!: " << path;
+        return false;
+    }
+
+    this->mediaItem = libvlc_media_new_fd(this->inst, this->currentFile->handle());
+    if(this->mediaItem == NULL) {
+        qDebug() << "Creating media failed: " << path;
+        return false;
+    }
+
+    return true;
+}
+
 void Core::ConcretePlayer::releaseMedia() {
+    this->cancelFade();
     this->mediaLoaded = false;
     if(this->mediaLoaded) {
         libvlc_media_release(this->mediaItem);
     }
 }
 
-void Core::ConcretePlayer::play() {
+void Core::ConcretePlayer::play(bool fadeIn) {
     if(this->mediaLoaded) {
+        auto fadeDuration = fadeIn ? this->setting->getFadeDuration() : 0;
+        if(fadeDuration > 0) {
+            // while a pause is still fading out the player is playing, the fade in turns around from there
+            if(this->getCurrentState() != libvlc_Playing) {
+                this->fadeLevel = 0.0;
+                libvlc_audio_set_volume(this->mediaPlayer, 0);
+            }
+        } else {
+            this->cancelFade();
+            this->fadeLevel = 1.0;
+            libvlc_audio_set_volume(this->mediaPlayer, this->volume);
+        }
+
         libvlc_media_player_play(this->mediaPlayer);
         this->autoPlay = true;
+
+        if(fadeDuration > 0) {
+            this->fade(true, fadeDuration);
+        }
+    } else if(this->isLoadPending) {
+        // starts once the retry has loaded the file
+        this->autoPlay = true;
     }
 }
 
-void Core::ConcretePlayer::stop() {
+void Core::ConcretePlayer::resume() {
+    auto rewindSeconds = this->setting->getPauseRewindSeconds();
+    if(this->mediaLoaded && rewindSeconds > 0 && this->getCurrentState() == libvlc_Paused) {
+        auto currentTime = static_cast<long long>(this->getCurrentTime());
+        this->updateSeekPosition(std::max(0LL, currentTime - rewindSeconds * 1000LL));
+    }
+
+    this->play(true);
+}
+
+void Core::ConcretePlayer::stop(bool fadeOut) {
+    auto fadeDuration = fadeOut ? this->setting->getFadeDuration() : 0;
+    if(fadeDuration > 0 && this->getCurrentState() == libvlc_Playing) {
+        // the player is paused once the fade is over
+        this->fade(false, fadeDuration);
+        return;
+    }
+
+    // cancel a fade that is still running
+    this->cancelFade();
+    this->fadeLevel = 1.0;
     libvlc_media_player_pause(this->mediaPlayer);
+    libvlc_audio_set_volume(this->mediaPlayer, this->volume);
+}
+
+void Core::ConcretePlayer::fade(bool fadeIn, int duration) {
+    int fadeId = ++this->currentFadeId;
+
+    // play and stop are also called from the thread that handles the end of a file
+    QMetaObject::invokeMethod(this, "startFade", Q_ARG(bool, fadeIn), Q_ARG(int, duration), Q_ARG(int, fadeId));
+}
+
+void Core::ConcretePlayer::cancelFade() {
+    this->currentFadeId++;
+
+    // from other threads the timer stops itself on its next step
+    if(QThread::currentThread() == this->thread()) {
+        this->fadeTimer->stop();
+    }
+}
+
+void Core::ConcretePlayer::startFade(bool fadeIn, int duration, int fadeId) {
+    // a newer play or pause took over before this got to run
+    if(this->currentFadeId != fadeId) {
+        return;
+    }
+
+    // start from wherever a fade that was cut short left the volume, the duration is for the full range
+    this->runningFadeId = fadeId;
+    this->isFadingIn = fadeIn;
+    this->fadeStartLevel = this->fadeLevel;
+    double distance = fadeIn ? 1.0 - this->fadeStartLevel : this->fadeStartLevel;
+    this->fadeSteps = std::max(1, static_cast<int>(duration * distance) / FADE_STEP_MS);
+    this->fadeStep = 0;
+    this->fadeTimer->start();
+}
+
+void Core::ConcretePlayer::stepFade() {
+    // a newer play or pause took over
+    if(this->currentFadeId != this->runningFadeId) {
+        this->fadeTimer->stop();
+        return;
+    }
+
+    this->fadeStep++;
+    double progress = static_cast<double>(this->fadeStep) / this->fadeSteps;
+    double targetLevel = this->isFadingIn ? 1.0 : 0.0;
+    double level = this->fadeStartLevel + (targetLevel - this->fadeStartLevel) * progress;
+    this->fadeLevel = level;
+    libvlc_audio_set_volume(this->mediaPlayer, static_cast<int>(this->volume * level));
+
+    if(this->fadeStep < this->fadeSteps) {
+        return;
+    }
+
+    this->fadeTimer->stop();
+    if(!this->isFadingIn) {
+        libvlc_media_player_set_pause(this->mediaPlayer, 1);
+    }
+
+    // back to the volume the user has chosen
+    this->fadeLevel = 1.0;
+    libvlc_audio_set_volume(this->mediaPlayer, this->volume);
 }
 
 void Core::ConcretePlayer::setupVLCCallbacks() {
@@ -126,7 +324,19 @@ void Core::ConcretePlayer::setupVLCCallbacks() {
 
                             player->threadPool->enqueue([data, player]() {
                                 if (player->mediaLoaded) {
-                                    emit player->timeProgressed(player->getCurrentTime());
+                                    auto currentTime = player->getCurrentTime();
+                                    if(player->performRepeat(currentTime)) {
+                                        return;
+                                    }
+
+                                    auto now = QDateTime::currentMSecsSinceEpoch();
+                                    auto interval = Core::effectiveTickInterval(player->tickInterval, player->isSeeking);
+                                    if(now - player->lastTickAt < interval) {
+                                        return;
+                                    }
+                                    player->lastTickAt = now;
+
+                                    emit player->timeProgressed(currentTime);
                                 }
                             });
 
@@ -150,6 +360,10 @@ void Core::ConcretePlayer::setupVLCCallbacks() {
                         libvlc_MediaPlayerEndReached, ConcretePlayer::handleFinished, this);
 }
 
+QString Core::ConcretePlayer::getVlcVersion() {
+    return QString::fromUtf8(libvlc_get_version());
+}
+
 libvlc_state_t Core::ConcretePlayer::getCurrentState() {
     if(this->mediaPlayer == nullptr) {
         return libvlc_NothingSpecial;
@@ -174,6 +388,14 @@ long long Core::ConcretePlayer::getDurationInMs() {
     return durationInMs;
 }
 
+long long Core::ConcretePlayer::getLengthInMs() {
+    if(!this->mediaLoaded) {
+        return -1;
+    }
+
+    return libvlc_media_player_get_length(this->mediaPlayer);
+}
+
 double Core::ConcretePlayer::getDurationInSeconds() {
     int durationInMs = libvlc_media_get_duration(this->mediaItem);
     if(durationInMs == -1) {
@@ -215,6 +437,9 @@ void Core::ConcretePlayer::setVolume(int volume) {
 
 Core::ConcretePlayer::~ConcretePlayer() {
     qDebug() << "Player destructor called";
+
+    // the fade uses the media player, it must not step again once the player is going away
+    this->fadeTimer->stop();
 }
 
 void Core::ConcretePlayer::setupMediaCallbacks() {
@@ -240,9 +465,11 @@ void Core::ConcretePlayer::setupMediaCallbacks() {
                             if(parsedStatus) {
                                 emit player->parsedStatusChanged(true);
 
-                                // load the current time if possible
+                                // load the current time if possible, the resume position needs the database
+                                // so it is worked out on the player's own thread
                                 if(!player->audiobookFileProxy->currentTimeNull()) {
-                                    player->updateSeekPosition(player->audiobookFileProxy->getCurrentTime());
+                                    QMetaObject::invokeMethod(player, "restoreResumePosition", Qt::QueuedConnection,
+                                                              Q_ARG(QString, player->audiobookFileProxy->path()));
                                 }
                             } else {
                                 emit player->parsedStatusChanged(false);
@@ -257,10 +484,132 @@ void Core::ConcretePlayer::playNextFile() {
     if(this->audiobookFileProxy != nullptr) {
         auto record = this->audiobookFileProxy->getNextFile();
         if(!record.getNullState()) {
+            // the next file has been kept out of auto-play, stop here instead
+            auto nextFile = this->proxyManager->getAudiobookFileProxy(record.getRecord());
+            if(nextFile->getSkipAutoAdvance()) {
+                return;
+            }
+
+
             this->loadMedia(record.getRecord());
             this->play();
+        } else {
+            // this was the last file of the audiobook
+            switch(this->setting->getBookEndBehavior()) {
+                case BookEndLoop:
+                    this->playFromFirstFile();
+                    break;
+                case BookEndNextInQueue:
+                    this->playNextQueuedAudiobook();
+                    break;
+                case BookEndNextUnfinished:
+                    this->playNextUnfinishedAudiobook();
+                    break;
+                case BookEndStop:
+                default:
+                    break;
+            }
+        }
+    }
+}
+
+void Core::ConcretePlayer::playFromFirstFile() {
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(this->audiobookFileProxy->getRecord().value("audiobook_id").toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook file list query failed: " << query.lastError().databaseText();
+        return;
+    }
+
+    while(query.next()) {
+        auto file = this->proxyManager->getAudiobookFileProxy(query.record());
+        if(!file->fileExists()) {
+            continue;
+        }
+
+        // the files stay finished, only the position goes back to the start
+        file->saveCurrentTime(0);
+        this->loadMedia(file->getRecord());
+        this->play();
+        return;
+    }
+}
+
+bool Core::ConcretePlayer::playNextQueuedAudiobook() {
+    auto audiobookId = this->setting->takeNextQueuedAudiobook();
+
+    while(audiobookId != -1) {
+        QSqlQuery query;
+        query.prepare("SELECT * FROM audiobooks WHERE id = ?");
+        query.addBindValue(audiobookId);
+
+        if(query.exec() && query.next()) {
+            auto audiobook = this->proxyManager->getAudiobookProxy(query.record());
+            auto file = audiobook->getFirstUnfinishedFile();
+
+            if(file != nullptr) {
+                this->releaseMedia();
+                this->loadMedia(file->getRecord());
+                this->play();
+
+                emit this->audiobookChanged(audiobookId);
+                return true;
+            }
         }
+
+        // the audiobook was removed or has nothing playable, move on to the next one
+        audiobookId = this->setting->takeNextQueuedAudiobook();
     }
+
+    return false;
+}
+
+bool Core::ConcretePlayer::playNextUnfinishedAudiobook() {
+    QSqlQuery currentQuery;
+    currentQuery.prepare("SELECT * FROM audiobooks WHERE id = ?");
+    currentQuery.addBindValue(this->audiobookFileProxy->getRecord().value("audiobook_id").toInt());
+    if(!currentQuery.exec() || !currentQuery.next()) {
+        qWarning() << "audiobook lookup failed: " << currentQuery.lastError().databaseText();
+        return false;
+    }
+    auto currentRecord = currentQuery.record();
+
+    // books of a series are usually numbered, so the ones after this one by name are the candidates
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE directory = ? AND id != ? AND "
+                  "(name > ? COLLATE NOCASE OR (name = ? COLLATE NOCASE AND id > ?)) "
+                  "ORDER BY name COLLATE NOCASE ASC, id ASC");
+    query.addBindValue(currentRecord.value("directory").toString());
+    query.addBindValue(currentRecord.value("id").toInt());
+    query.addBindValue(currentRecord.value("name").toString());
+    query.addBindValue(currentRecord.value("name").toString());
+    query.addBindValue(currentRecord.value("id").toInt());
+    if(!query.exec()) {
+        qWarning() << "next audiobook query failed: " << query.lastError().databaseText();
+        return false;
+    }
+
+    while(query.next()) {
+        auto audiobook = this->proxyManager->getAudiobookProxy(query.record());
+        if(audiobook->getCompleteness() >= 100) {
+            continue;
+        }
+
+        auto file = audiobook->getFirstUnfinishedFile();
+        if(file == nullptr) {
+            continue;
+        }
+
+        this->releaseMedia();
+        this->loadMedia(file->getRecord());
+        this->play();
+
+        emit this->audiobookChanged(audiobook->getId());
+        return true;
+    }
+
+    return false;
 }
 
 void Core::ConcretePlayer::handleFinished(const libvlc_event_t *event, void *data) {
@@ -285,6 +634,196 @@ void Core::ConcretePlayer::setSpeed(QString speed) {
 }
 
 float Core::ConcretePlayer::getRate() {
-    return this->speed.toFloat();
+    // vlc refuses rates it can't play, an unreadable speed falls back to normal speed
+    bool isNumber = false;
+    auto rate = this->speed.toDouble(&isNumber);
+    if(!isNumber || rate <= 0) {
+        return 1.0f;
+    }
+
+    return static_cast<float>(qBound(MIN_SPEED, rate, MAX_SPEED));
+}
+
+void Core::ConcretePlayer::setRepeatStart() {
+    if(!this->mediaLoaded) {
+        return;
+    }
+
+    auto currentTime = this->getCurrentTime();
+
+    // the B point must come after the A point, drop it before moving A so
+    // the time changed callback never sees a B point before the A point
+    if(this->hasRepeatEnd && this->repeatEnd <= currentTime) {
+        this->hasRepeatEnd = false;
+    }
+
+    this->repeatStart = currentTime;
+    this->hasRepeatStart = true;
+
+    emit this->repeatChanged();
+}
+
+void Core::ConcretePlayer::setRepeatEnd() {
+    if(!this->mediaLoaded || !this->hasRepeatStart) {
+        return;
+    }
+
+    auto currentTime = this->getCurrentTime();
+    // ignore a B point that is before or on the A point
+    if(currentTime <= this->repeatStart) {
+        return;
+    }
+
+    this->repeatEnd = currentTime;
+    this->hasRepeatEnd = true;
+
+    emit this->repeatChanged();
+}
+
+void Core::ConcretePlayer::clearRepeat() {
+    if(!this->hasRepeatStart && !this->hasRepeatEnd) {
+        return;
+    }
+
+    this->hasRepeatStart = false;
+    this->hasRepeatEnd = false;
+
+    emit this->repeatChanged();
+}
+
+bool Core::ConcretePlayer::isRepeatStartSet() {
+    return this->hasRepeatStart;
+}
+
+bool Core::ConcretePlayer::isRepeatEndSet() {
+    return this->hasRepeatEnd;
 }
 
+bool Core::ConcretePlayer::performRepeat(long long currentTime) {
+    if(!this->hasRepeatStart || !this->hasRepeatEnd) {
+        return false;
+    }
+
+    if(currentTime < this->repeatEnd) {
+        return false;
+    }
+
+    this->updateSeekPosition(this->repeatStart);
+    return true;
+}
+
+void Core::ConcretePlayer::rememberSeekPosition() {
+    if(!this->mediaLoaded) {
+        return;
+    }
+
+    this->seekHistory.push_back(this->getCurrentTime());
+    if(this->seekHistory.size() > MAX_SEEK_HISTORY) {
+        this->seekHistory.erase(this->seekHistory.begin());
+    }
+}
+
+bool Core::ConcretePlayer::undoSeek() {
+    if(!this->mediaLoaded || this->seekHistory.empty()) {
+        return false;
+    }
+
+    auto position = this->seekHistory.back();
+    this->seekHistory.pop_back();
+    this->updateSeekPosition(position);
+
+    return true;
+}
+
+void Core::ConcretePlayer::setTickInterval(int milliseconds) {
+    this->tickInterval = milliseconds;
+}
+
+void Core::ConcretePlayer::setPauseOnOutputRemoved(bool pauseOnOutputRemoved) {
+    if(pauseOnOutputRemoved) {
+        this->knownOutputDevices = this->getOutputDevices();
+        this->outputDeviceTimer->start();
+    } else {
+        this->outputDeviceTimer->stop();
+    }
+}
+
+QStringList Core::ConcretePlayer::getOutputDevices() {
+    QStringList devices;
+
+    // outputs that can't list their devices return nothing, then a removed device is never noticed
+    auto deviceList = libvlc_audio_output_device_enum(this->mediaPlayer);
+    for(auto device = deviceList; device != nullptr; device = device->p_next) {
+        devices << QString::fromUtf8(device->psz_device);
+    }
+    libvlc_audio_output_device_list_release(deviceList);
+
+    return devices;
+}
+
+void Core::ConcretePlayer::checkOutputDevices() {
+    auto devices = this->getOutputDevices();
+
+    bool deviceRemoved = false;
+    for(auto &device : this->knownOutputDevices) {
+        if(!devices.contains(device)) {
+            deviceRemoved = true;
+            break;
+        }
+    }
+    this->knownOutputDevices = devices;
+
+    if(!deviceRemoved) {
+        return;
+    }
+
+    emit this->audioOutputRemoved();
+
+    // pause right away, fading out would still play through the speakers
+    if(this->getCurrentState() == libvlc_Playing) {
+        this->cancelFade();
+        this->fadeLevel = 1.0;
+        libvlc_media_player_set_pause(this->mediaPlayer, 1);
+        libvlc_audio_set_volume(this->mediaPlayer, this->volume);
+    }
+}
+
+void Core::ConcretePlayer::setSeeking(bool seeking) {
+    this->isSeeking = seeking;
+}
+
+void Core::ConcretePlayer::restoreResumePosition(QString path) {
+    // another file may have been loaded while this was queued
+    if(!this->mediaLoaded || this->audiobookFileProxy == nullptr || this->audiobookFileProxy->path() != path) {
+        return;
+    }
+
+    this->updateSeekPosition(this->getResumePosition());
+}
+
+long long Core::ConcretePlayer::getResumePosition() {
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->audiobookFileProxy->getRecord().value("audiobook_id").toInt());
+    if(query.exec() && query.next()) {
+        auto audiobook = this->proxyManager->getAudiobookProxy(query.record());
+        if(audiobook->getResumeMode() == ResumeStartOfFile) {
+            return 0;
+        }
+    }
+
+    auto currentTime = this->audiobookFileProxy->getCurrentTime();
+    auto rewindSeconds = this->setting->getSmartResumeSeconds();
+    auto lastPlayedAt = this->audiobookFileProxy->getLastPlayedAt();
+
+    if(rewindSeconds <= 0 || !lastPlayedAt.isValid()) {
+        return currentTime;
+    }
+
+    // a short pause doesn't need any context
+    if(lastPlayedAt.secsTo(QDateTime::currentDateTimeUtc()) <= SMART_RESUME_MIN_GAP_SECS) {
+        return currentTime;
+    }
+
+    return std::max(0LL, currentTime - rewindSeconds * 1000LL);
+}
@@ -6,12 +6,17 @@
 #define NODOKANATIVE_MEDIAPLAYER_H
 
 #include <memory>
+#include <atomic>
+#include <vector>
 #include <QObject>
 #include <src/proxy-objects/AudiobookFileProxy.h>
 #include <src/simple-lib/ThreadPool.h>
 #include "vlc/vlc.h"
 #include "Setting.h"
 #include <QFile>
+#include <QTimer>
+#include <QElapsedTimer>
+#include <QStringList>
 #include <src/proxy-objects/ProxyManager.h>
 
 namespace Core {
@@ -34,6 +39,48 @@ namespace Core {
         bool hasSeekTo;
         bool autoPlay;
 
+        // A-B repeat points, only valid within the currently loaded file
+        // set from the ui thread and read by the time changed callback, a point is written before its flag
+        std::atomic<long long> repeatStart;
+        std::atomic<long long> repeatEnd;
+        std::atomic<bool> hasRepeatStart;
+        std::atomic<bool> hasRepeatEnd;
+
+        // positions left by manual seeks in the currently loaded file, the latest one is at the back
+        std::vector<long long> seekHistory;
+
+        // seek back to the A point if the B point has been passed, returns true if it did
+        bool performRepeat(long long currentTime);
+
+        // ramp the live vlc volume up or down, the volume setting itself is never touched
+        // the timer lives on the ui thread, a fade can be started or cancelled from any thread through the id
+        QTimer* fadeTimer;
+        std::atomic<int> currentFadeId;
+        int runningFadeId;
+        bool isFadingIn;
+        // the share of the volume that is playing right now, 1 when no fade is running
+        std::atomic<double> fadeLevel;
+        double fadeStartLevel;
+        int fadeSteps;
+        int fadeStep;
+        void fade(bool fadeIn, int duration);
+        void cancelFade();
+
+        // where the loaded file should start: the saved position, rewound a little if the user has been away
+        // for a while, or the start of the file when the audiobook is set to resume from there
+        long long getResumePosition();
+
+        // position updates are passed on at most once per tick interval
+        std::atomic<int> tickInterval;
+        std::atomic<bool> isSeeking;
+        std::atomic<qint64> lastTickAt;
+
+        // vlc 2.2 has no event for device changes, so the device list is compared on a timer
+        QTimer* outputDeviceTimer;
+        QStringList knownOutputDevices;
+        QStringList getOutputDevices();
+        void checkOutputDevices();
+
         // thread pool manager
         std::unique_ptr<ThreadPool> threadPool;
 
@@ -49,6 +96,14 @@ namespace Core {
         // set up the vlc callbacks
         void setupVLCCallbacks();
 
+        // open the file and create the vlc media item for it, false if either step failed
+        bool openMediaItem(QString path);
+
+        // finish loading path, a failed attempt is retried later from the ui thread
+        QElapsedTimer loadTimer;
+        std::atomic<bool> isLoadPending;
+        void tryLoadMedia(QString path, int attempt);
+
         // media item related callbacks
         void setupMediaCallbacks();
 
@@ -57,33 +112,76 @@ namespace Core {
 
         static void handleFinished(const struct libvlc_event_t* event, void* data);
 
+        // start the next audiobook in the queue, returns false if there was nothing to play
+        bool playNextQueuedAudiobook();
+        // start the next audiobook after this one in the same directory that isn't finished yet,
+        // returns false if there is none
+        bool playNextUnfinishedAudiobook();
+        // go back to the first file of the current audiobook and keep playing
+        void playFromFirstFile();
+
     public:
         ConcretePlayer(Setting* setting, std::shared_ptr<ProxyManager> manager);
         ~ConcretePlayer();
+
+        // version of the libvlc that was loaded at runtime, e.g. "3.0.18 Vetinari"
+        static QString getVlcVersion();
+
         std::shared_ptr<AudiobookFileProxy> getAudiobookFile();
         libvlc_state_t getCurrentState();
         libvlc_time_t getCurrentTime();
-        void play();
+        // only playback started or paused by the user fades, not moving on to the next file
+        void play(bool fadeIn = false);
+        // play with a fade, but coming back from a pause goes back a couple of seconds first
+        void resume();
         void playNextFile();
-        void stop();
+        void stop(bool fadeOut = false);
         void loadMedia(QSqlRecord record);
         void releaseMedia();
         void setVolume(int volume);
         void setSpeed(QString speed);
         long long getDurationInMs();
+        // length reported by the playing media, some streamed or VBR files only know it once playback has started
+        long long getLengthInMs();
         double getDurationInSeconds();
         float getRate();
 
         bool canLoadMedia(QSqlRecord record);
 
+        // A-B repeat, the points are taken from the current playback time
+        void setRepeatStart();
+        void setRepeatEnd();
+        void clearRepeat();
+        bool isRepeatStartSet();
+        bool isRepeatEndSet();
+
+        // call before a seek made by the user, so it can be undone
+        void rememberSeekPosition();
+        // go back to where the last manual seek left from, returns false if there is nothing to undo
+        bool undoSeek();
+
+        // while the user drags the position around every update is passed on, not just one per tick
+        void setSeeking(bool seeking);
+
     signals:
         void stateChanged(libvlc_state_t newState);
         void timeProgressed(libvlc_time_t time);
         void parsedStatusChanged(bool isParsed);
         void currentFileFinished();
+        void repeatChanged();
+        void audiobookChanged(int audiobookId);
+        void audioOutputRemoved();
+
+    private slots:
+        void scheduleLoadRetry(QString path, int attempt);
+        void startFade(bool fadeIn, int duration, int fadeId);
+        void stepFade();
+        void restoreResumePosition(QString path);
 
     public slots:
         void updateSeekPosition(long long position);
+        void setTickInterval(int milliseconds);
+        void setPauseOnOutputRemoved(bool pauseOnOutputRemoved);
 
     };
 }
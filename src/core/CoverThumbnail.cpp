@@ -0,0 +1,38 @@
+#include <QImage>
+#include <QImageReader>
+#include "CoverThumbnail.h"
+
+bool Core::createCoverThumbnail(QString source, QString destination, int size, QString* errorMessage) {
+    QImageReader reader(source);
+    QString message;
+
+    if(!reader.canRead()) {
+        message = "unsupported or damaged image: " + reader.errorString();
+    } else {
+        // decode straight to the thumbnail size, a huge cover doesn't need to be held in memory in full
+        auto imageSize = reader.size();
+        if(imageSize.isValid() && (imageSize.width() > size || imageSize.height() > size)) {
+            reader.setScaledSize(imageSize.scaled(size, size, Qt::KeepAspectRatio));
+        }
+
+        QImage image = reader.read();
+        if(image.isNull()) {
+            message = "failed to decode image: " + reader.errorString();
+        } else {
+            // the reader may not support scaled decoding for every format
+            if(image.width() > size || image.height() > size) {
+                image = image.scaled(size, size, Qt::KeepAspectRatio, Qt::SmoothTransformation);
+            }
+
+            if(!image.isNull() && image.save(destination, "PNG")) {
+                return true;
+            }
+            message = "failed to save thumbnail to " + destination;
+        }
+    }
+
+    if(errorMessage != nullptr) {
+        *errorMessage = message;
+    }
+    return false;
+}
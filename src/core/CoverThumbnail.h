@@ -0,0 +1,14 @@
+#ifndef NODOKANATIVE_COVERTHUMBNAIL_H
+#define NODOKANATIVE_COVERTHUMBNAIL_H
+
+#include <QString>
+
+namespace Core {
+
+    // decode the image at source scaled down to fit size x size and save it as a png at destination,
+    // a damaged or unsupported image returns false with the reason in errorMessage
+    bool createCoverThumbnail(QString source, QString destination, int size, QString* errorMessage = nullptr);
+}
+
+
+#endif //NODOKANATIVE_COVERTHUMBNAIL_H
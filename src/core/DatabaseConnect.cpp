@@ -10,22 +10,85 @@
 #include "Util.h"
 #include <QtCore/QDir>
 #include <QDebug>
+#include <QSqlRecord>
+#include <QDateTime>
+#include <QFile>
 
-bool ::Core::openDb() {
-    QSqlDatabase db = QSqlDatabase::addDatabase("QSQLITE");
+// sqlite result codes for a damaged file and for a file that isn't a database at all
+const static char* SQLITE_CORRUPT_CODE = "11";
+const static char* SQLITE_NOTADB_CODE = "26";
+const static char* SQLITE_BUSY_CODE = "5";
+const static char* SQLITE_LOCKED_CODE = "6";
 
-    // ensure the path gets created for settings, if not already exists
-    createSettingPathIfNotExists();
+static QString recoveryNotice;
 
-    auto dbFilePath = QDir(getSettingPath() + "/nodoka.db").absolutePath();
-    db.setDatabaseName(dbFilePath);
+static bool isCorruptionError(const QSqlError &error) {
+    return error.nativeErrorCode() == SQLITE_CORRUPT_CODE || error.nativeErrorCode() == SQLITE_NOTADB_CODE;
+}
 
-    qDebug() << "db file set to: " << dbFilePath;
+static bool isLockedError(const QSqlError &error) {
+    return error.nativeErrorCode() == SQLITE_BUSY_CODE || error.nativeErrorCode() == SQLITE_LOCKED_CODE;
+}
 
-    if(!db.open()) {
+// returns false if the database can't be read, sets corrupt if that's because the file is damaged
+static bool checkIntegrity(bool* corrupt, QString* errorMessage) {
+    *corrupt = false;
+
+    QSqlQuery query;
+    if(!query.exec("PRAGMA quick_check")) {
+        *corrupt = isCorruptionError(query.lastError());
+        if(isLockedError(query.lastError())) {
+            *errorMessage = "The database is locked by another program";
+        } else {
+            *errorMessage = query.lastError().databaseText();
+        }
         return false;
     }
 
+    if(query.next() && query.value(0).toString() != "ok") {
+        *corrupt = true;
+        *errorMessage = query.value(0).toString();
+        return false;
+    }
+
+    return true;
+}
+
+// move a damaged database and its journal files out of the way, so a new one can be created in its place
+static bool moveCorruptDatabase(QString dbFilePath, QString* movedTo) {
+    *movedTo = dbFilePath + ".corrupt-" + QDateTime::currentDateTime().toString("yyyyMMddHHmmss");
+    if(!QFile::rename(dbFilePath, *movedTo)) {
+        qWarning() << "Failed to move the corrupt database to " << *movedTo;
+        return false;
+    }
+
+    for(auto &suffix : QStringList({"-wal", "-shm"})) {
+        if(QFile::exists(dbFilePath + suffix)) {
+            QFile::rename(dbFilePath + suffix, *movedTo + suffix);
+        }
+    }
+
+    return true;
+}
+
+// add a column to an existing table if an older config file does not have it yet
+static bool addColumnIfNotExists(QString table, QString column, QString definition) {
+    auto record = QSqlDatabase::database().record(table);
+    if(record.contains(column)) {
+        return true;
+    }
+
+    QSqlQuery query;
+    auto response = query.exec("ALTER TABLE " + table + " ADD COLUMN " + column + " " + definition);
+    if(!response) {
+        qWarning() << "Failed to add column " << column << " to " << table << ": "
+                   << query.lastError().databaseText();
+    }
+
+    return response;
+}
+
+static bool createSchema() {
     QSqlQuery query;
     bool pragmaResponse = false;
     pragmaResponse = query.exec("PRAGMA journal_mode=WAL;");
@@ -48,7 +111,26 @@ bool ::Core::openDb() {
     response = query.exec("CREATE TABLE IF NOT EXISTS directories("
                        "full_path text PRIMARY KEY,"
                        "created_at text,"
-                       "last_scanned text"
+                       "last_scanned text,"
+                       "enabled INTEGER DEFAULT 1,"
+                       "collection_id INTEGER"
+                       ")");
+
+    if(!response) {
+        return false;
+    }
+
+    if(!addColumnIfNotExists("directories", "enabled", "INTEGER DEFAULT 1")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("directories", "collection_id", "INTEGER")) {
+        return false;
+    }
+
+    // named groups of scanning directories, e.g. books spread over two drives
+    response = query.exec("CREATE TABLE IF NOT EXISTS collections("
+                       "id INTEGER PRIMARY KEY AUTOINCREMENT,"
+                       "name TEXT UNIQUE"
                        ")");
 
     if(!response) {
@@ -63,7 +145,20 @@ bool ::Core::openDb() {
                        "completeness INTEGER,"
                        "default_order INTEGER,"
                        "selected_file TEXT,"
-                       "created_at TEXT"
+                       "created_at TEXT,"
+                       "playback_speed TEXT,"
+                       "title TEXT,"
+                       "author TEXT,"
+                       "last_played_at TEXT,"
+                       "finished_at TEXT,"
+                       "has_gaps INTEGER DEFAULT 0,"
+                       "resume_mode INTEGER DEFAULT 0,"
+                       "manual_order INTEGER DEFAULT 0,"
+                       "listened_ms INTEGER DEFAULT 0,"
+                       "cover_override_path TEXT,"
+                       "playback_volume INTEGER,"
+                       "file_sort INTEGER,"
+                       "display_name TEXT"
                        ")");
 
     if(!response) {
@@ -71,6 +166,45 @@ bool ::Core::openDb() {
         return false;
     }
 
+    // columns added after the initial release
+    if(!addColumnIfNotExists("audiobooks", "playback_speed", "TEXT")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "title", "TEXT") ||
+       !addColumnIfNotExists("audiobooks", "author", "TEXT")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "last_played_at", "TEXT")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "finished_at", "TEXT")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "has_gaps", "INTEGER DEFAULT 0")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "resume_mode", "INTEGER DEFAULT 0")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "manual_order", "INTEGER DEFAULT 0")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "listened_ms", "INTEGER DEFAULT 0")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "cover_override_path", "TEXT")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "playback_volume", "INTEGER")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "file_sort", "INTEGER")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobooks", "display_name", "TEXT")) {
+        return false;
+    }
+
     response = query.exec("CREATE TABLE IF NOT EXISTS audiobook_file("
                        "audiobook_id INTEGER,"
                        "name TEXT,"
@@ -80,7 +214,12 @@ bool ::Core::openDb() {
                        "position INTEGER,"
                        "completeness INTEGER,"
                        "file_exists BOOL,"
-                       "created_at TEXT"
+                       "created_at TEXT,"
+                       "title TEXT,"
+                       "artist TEXT,"
+                       "album TEXT,"
+                       "track_number INTEGER,"
+                       "skip_auto_advance INTEGER DEFAULT 0"
                        ")");
 
     if(!response) {
@@ -88,6 +227,39 @@ bool ::Core::openDb() {
         return false;
     }
 
+    if(!addColumnIfNotExists("audiobook_file", "title", "TEXT") ||
+       !addColumnIfNotExists("audiobook_file", "artist", "TEXT") ||
+       !addColumnIfNotExists("audiobook_file", "album", "TEXT") ||
+       !addColumnIfNotExists("audiobook_file", "track_number", "INTEGER")) {
+        return false;
+    }
+    if(!addColumnIfNotExists("audiobook_file", "skip_auto_advance", "INTEGER DEFAULT 0")) {
+        return false;
+    }
+
+    // audiobook fields from a library import, applied once the audiobook is registered
+    response = query.exec("CREATE TABLE IF NOT EXISTS pending_imports("
+                       "full_path TEXT PRIMARY KEY,"
+                       "selected_file TEXT,"
+                       "playback_speed TEXT,"
+                       "last_played_at TEXT,"
+                       "finished_at TEXT"
+                       ")");
+
+    if(!response) {
+        return false;
+    }
+
+    // folders of duplicate audiobooks that were merged into another copy
+    response = query.exec("CREATE TABLE IF NOT EXISTS merged_duplicates("
+                       "full_path TEXT PRIMARY KEY,"
+                       "created_at TEXT"
+                       ")");
+
+    if(!response) {
+        return false;
+    }
+
     query.exec("CREATE INDEX IF NOT EXISTS audiobook_dir_index ON audiobooks(directory)");
     query.exec("CREATE INDEX IF NOT EXISTS audiobook_full_path_index ON audiobooks(full_path)");
     query.exec("CREATE INDEX IF NOT EXISTS audiobook_ab_id_index ON audiobook_file(audiobook_id)");
@@ -95,3 +267,58 @@ bool ::Core::openDb() {
 
     return true;
 }
+
+bool ::Core::openDb(QString* errorMessage) {
+    QString message;
+    QSqlDatabase db = QSqlDatabase::addDatabase("QSQLITE");
+
+    // ensure the path gets created for settings, if not already exists
+    if(!checkSettingPath(&message)) {
+        if(errorMessage != nullptr) {
+            *errorMessage = message;
+        }
+        return false;
+    }
+
+    auto dbFilePath = QDir(getSettingPath() + "/nodoka.db").absolutePath();
+    db.setDatabaseName(dbFilePath);
+
+    qDebug() << "db file set to: " << dbFilePath;
+
+    if(!db.open()) {
+        message = db.lastError().databaseText();
+    } else {
+        bool corrupt = false;
+        if(checkIntegrity(&corrupt, &message)) {
+            if(createSchema()) {
+                return true;
+            }
+            message = "The database schema could not be created";
+        } else if(corrupt) {
+            qWarning() << "Database is corrupt: " << message;
+            db.close();
+
+            // the per file progress lives in its own setting files, so it comes back once the directories are added again
+            QString movedTo;
+            if(moveCorruptDatabase(dbFilePath, &movedTo) && db.open() && createSchema()) {
+                recoveryNotice = "The library database was damaged and has been reset. "
+                                 "The damaged copy was kept at " + movedTo + ". "
+                                 "Add your directories again to restore your audiobooks.";
+                qWarning() << "Database recovered, corrupt copy moved to " << movedTo;
+                return true;
+            }
+            message = "The database is damaged and could not be replaced";
+        }
+    }
+
+    qWarning() << "Failed to open the database: " << message;
+    if(errorMessage != nullptr) {
+        *errorMessage = message;
+    }
+
+    return false;
+}
+
+QString Core::getDatabaseRecoveryNotice() {
+    return recoveryNotice;
+}
@@ -7,10 +7,15 @@
 
 #include <Qt>
 #include <QMutexLocker>
+#include <QString>
 
 namespace Core {
-    // Open a connection to the database, if it fails, it will return false
-    bool openDb();
+    // Open a connection to the database, if it fails, it will return false and set errorMessage
+    // A corrupt database is moved aside and replaced by an empty one
+    bool openDb(QString* errorMessage = nullptr);
+
+    // Describes the recovery if openDb had to replace a corrupt database, empty otherwise
+    QString getDatabaseRecoveryNotice();
 }
 
 #endif //NODOKANATIVE_DATABASECONNECT_H
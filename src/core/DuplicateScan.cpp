@@ -0,0 +1,92 @@
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QStringList>
+#include <QMap>
+#include <QDebug>
+#include <QDateTime>
+#include "DuplicateScan.h"
+
+std::vector<std::vector<std::shared_ptr<AudiobookProxy>>> Core::findDuplicateAudiobooks(std::shared_ptr<ProxyManager> manager) {
+    std::vector<std::vector<std::shared_ptr<AudiobookProxy>>> duplicateGroups;
+
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM audiobooks ORDER BY id ASC")) {
+        qWarning() << "audiobook duplicate query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return duplicateGroups;
+    }
+
+    // audiobooks keyed by the sorted list of their file checksums
+    QMap<QString, std::vector<std::shared_ptr<AudiobookProxy>>> audiobooksByChecksums;
+    while(query.next()) {
+        auto audiobook = manager->getAudiobookProxy(query.record());
+        auto fileList = audiobook->getFilesForAudiobook();
+        if(fileList.empty()) {
+            continue;
+        }
+
+        QStringList checkSums;
+        bool allFilesChecked = true;
+        for(auto &file : fileList) {
+            auto checkSum = file->getCheckSum();
+            if(checkSum.isEmpty()) {
+                allFilesChecked = false;
+                break;
+            }
+            checkSums << checkSum;
+        }
+
+        if(!allFilesChecked) {
+            continue;
+        }
+
+        checkSums.sort();
+        audiobooksByChecksums[checkSums.join(",")].push_back(audiobook);
+    }
+
+    for(auto &audiobooks : audiobooksByChecksums) {
+        if(audiobooks.size() > 1) {
+            duplicateGroups.push_back(audiobooks);
+        }
+    }
+
+    return duplicateGroups;
+}
+
+void Core::markMergedDuplicate(QString path) {
+    QSqlQuery query;
+    query.prepare("INSERT OR REPLACE INTO merged_duplicates (full_path, created_at) VALUES (?, ?)");
+    query.addBindValue(path);
+    query.addBindValue(QDateTime::currentDateTimeUtc());
+    if(!query.exec()) {
+        qWarning() << "merged duplicate insert failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+void Core::clearMergedDuplicate(QString path) {
+    QSqlQuery query;
+    query.prepare("DELETE FROM merged_duplicates WHERE full_path = ?");
+    query.addBindValue(path);
+    if(!query.exec()) {
+        qWarning() << "merged duplicate delete failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+bool Core::isMergedDuplicate(QString path) {
+    QSqlQuery query;
+    query.prepare("SELECT full_path FROM merged_duplicates WHERE full_path = ?");
+    query.addBindValue(path);
+    if(!query.exec()) {
+        qWarning() << "merged duplicate query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return false;
+    }
+
+    return query.next();
+}
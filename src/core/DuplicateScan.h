@@ -0,0 +1,21 @@
+#ifndef NODOKANATIVE_DUPLICATESCAN_H
+#define NODOKANATIVE_DUPLICATESCAN_H
+
+#include <memory>
+#include <vector>
+#include <src/proxy-objects/ProxyManager.h>
+
+namespace Core {
+
+    // groups of audiobooks whose files have exactly the same checksums, audiobooks with files
+    // that haven't been checksummed yet are left out
+    std::vector<std::vector<std::shared_ptr<AudiobookProxy>>> findDuplicateAudiobooks(std::shared_ptr<ProxyManager> manager);
+
+    // folders of duplicates that were merged away, a rescan must not register them again
+    void markMergedDuplicate(QString path);
+    void clearMergedDuplicate(QString path);
+    bool isMergedDuplicate(QString path);
+}
+
+
+#endif //NODOKANATIVE_DUPLICATESCAN_H
@@ -0,0 +1,368 @@
+#include <QFile>
+#include <QDir>
+#include <QList>
+#include <QCryptographicHash>
+#include <QDebug>
+#include "Util.h"
+#include "EmbeddedCover.h"
+
+// the picture type of a front cover, the same number is used by id3v2 and flac
+const static int FRONT_COVER_TYPE = 3;
+
+// no cover is this big, a larger size means the tag is damaged
+const static qint64 MAX_TAG_SIZE = 32 * 1024 * 1024;
+
+static quint32 readBigEndian(const QByteArray& data, int offset, int length) {
+    quint32 value = 0;
+    for(int i = 0; i < length; i++) {
+        value = (value << 8) | static_cast<quint8>(data.at(offset + i));
+    }
+
+    return value;
+}
+
+static quint32 readSyncSafe(const QByteArray& data, int offset) {
+    quint32 value = 0;
+    for(int i = 0; i < 4; i++) {
+        value = (value << 7) | (static_cast<quint8>(data.at(offset + i)) & 0x7f);
+    }
+
+    return value;
+}
+
+// undo the id3v2 unsynchronisation, which puts a zero byte after every 0xff
+static QByteArray removeUnsynchronisation(const QByteArray& data) {
+    QByteArray result;
+    result.reserve(data.size());
+    for(int i = 0; i < data.size(); i++) {
+        result.append(data.at(i));
+        if(static_cast<quint8>(data.at(i)) == 0xff && i + 1 < data.size() && data.at(i + 1) == 0) {
+            i++;
+        }
+    }
+
+    return result;
+}
+
+// returns the position after a zero terminated text, the zero is two bytes wide for the utf-16 encodings
+static int skipTerminatedText(const QByteArray& data, int offset, int encoding) {
+    if(encoding == 1 || encoding == 2) {
+        for(int i = offset; i + 1 < data.size(); i += 2) {
+            if(data.at(i) == 0 && data.at(i + 1) == 0) {
+                return i + 2;
+            }
+        }
+        return -1;
+    }
+
+    auto end = data.indexOf('\0', offset);
+    return end == -1 ? -1 : end + 1;
+}
+
+// the image in an APIC frame, or a PIC frame for v2.2 tags
+static QByteArray readPictureFrame(const QByteArray& frame, bool isVersion22, int* pictureType) {
+    if(frame.size() < 2) {
+        return QByteArray();
+    }
+
+    int encoding = static_cast<quint8>(frame.at(0));
+    int offset = 1;
+    if(isVersion22) {
+        // a three letter image format instead of a mime type
+        offset += 3;
+    } else {
+        offset = frame.indexOf('\0', offset);
+        if(offset == -1) {
+            return QByteArray();
+        }
+        offset++;
+    }
+
+    if(offset >= frame.size()) {
+        return QByteArray();
+    }
+    *pictureType = static_cast<quint8>(frame.at(offset));
+
+    // skip the description
+    offset = skipTerminatedText(frame, offset + 1, encoding);
+    if(offset == -1 || offset >= frame.size()) {
+        return QByteArray();
+    }
+
+    return frame.mid(offset);
+}
+
+// tagEnd is set to the position after the tag, flac files can have an id3 tag in front
+static QByteArray readId3Cover(QFile& file, qint64* tagEnd) {
+    file.seek(0);
+    auto header = file.read(10);
+    if(header.size() < 10 || !header.startsWith("ID3")) {
+        return QByteArray();
+    }
+
+    int version = static_cast<quint8>(header.at(3));
+    int flags = static_cast<quint8>(header.at(5));
+    qint64 tagSize = readSyncSafe(header, 6);
+    // the size doesn't include the header or the footer
+    *tagEnd = 10 + tagSize + ((flags & 0x10) ? 10 : 0);
+
+    if(version < 2 || version > 4 || tagSize > MAX_TAG_SIZE) {
+        return QByteArray();
+    }
+
+    auto tag = file.read(tagSize);
+    if(version < 4 && (flags & 0x80)) {
+        tag = removeUnsynchronisation(tag);
+    }
+
+    int offset = 0;
+    if(version > 2 && (flags & 0x40) && tag.size() >= 4) {
+        // the v2.4 extended header size includes itself, the v2.3 one doesn't
+        qint64 extendedHeaderSize = version == 4 ? readSyncSafe(tag, 0) : readBigEndian(tag, 0, 4) + 4;
+        if(extendedHeaderSize > tag.size()) {
+            return QByteArray();
+        }
+        offset = static_cast<int>(extendedHeaderSize);
+    }
+
+    bool isVersion22 = version == 2;
+    int idLength = isVersion22 ? 3 : 4;
+    int headerLength = isVersion22 ? 6 : 10;
+
+    QByteArray cover;
+    while(offset + headerLength <= tag.size()) {
+        auto frameId = tag.mid(offset, idLength);
+        // the rest of the tag is padding
+        if(frameId.at(0) == 0) {
+            break;
+        }
+
+        qint64 frameSize;
+        if(isVersion22) {
+            frameSize = readBigEndian(tag, offset + 3, 3);
+        } else if(version == 4) {
+            frameSize = readSyncSafe(tag, offset + 4);
+        } else {
+            frameSize = readBigEndian(tag, offset + 4, 4);
+        }
+
+        int formatFlags = isVersion22 ? 0 : static_cast<quint8>(tag.at(offset + 9));
+        int frameStart = offset + headerLength;
+        if(frameSize <= 0 || frameStart + frameSize > tag.size()) {
+            break;
+        }
+        offset = frameStart + frameSize;
+
+        if(frameId != (isVersion22 ? "PIC" : "APIC")) {
+            continue;
+        }
+
+        auto frame = tag.mid(frameStart, frameSize);
+        if(version == 3) {
+            // compressed or encrypted frames can't be read
+            if(formatFlags & 0xc0) {
+                continue;
+            }
+        } else if(version == 4) {
+            if(formatFlags & 0x0c) {
+                continue;
+            }
+
+            // a group id and the data length can come before the frame data
+            int skip = ((formatFlags & 0x40) ? 1 : 0) + ((formatFlags & 0x01) ? 4 : 0);
+            frame = frame.mid(skip);
+            if(formatFlags & 0x02) {
+                frame = removeUnsynchronisation(frame);
+            }
+        }
+
+        int pictureType = 0;
+        auto picture = readPictureFrame(frame, isVersion22, &pictureType);
+        if(picture.isEmpty()) {
+            continue;
+        }
+
+        if(pictureType == FRONT_COVER_TYPE) {
+            return picture;
+        }
+        if(cover.isEmpty()) {
+            cover = picture;
+        }
+    }
+
+    return cover;
+}
+
+static QByteArray readFlacCover(QFile& file, qint64 start) {
+    file.seek(start);
+    if(file.read(4) != "fLaC") {
+        return QByteArray();
+    }
+
+    QByteArray cover;
+    bool isLastBlock = false;
+    while(!isLastBlock) {
+        auto blockHeader = file.read(4);
+        if(blockHeader.size() < 4) {
+            break;
+        }
+
+        isLastBlock = (static_cast<quint8>(blockHeader.at(0)) & 0x80) != 0;
+        int blockType = static_cast<quint8>(blockHeader.at(0)) & 0x7f;
+        qint64 blockSize = readBigEndian(blockHeader, 1, 3);
+
+        // 6 is a picture block
+        if(blockType != 6) {
+            file.seek(file.pos() + blockSize);
+            continue;
+        }
+
+        auto block = file.read(blockSize);
+        if(block.size() < 32) {
+            continue;
+        }
+
+        int pictureType = readBigEndian(block, 0, 4);
+        qint64 offset = 4;
+        // the mime type and the description, each with its length in front
+        for(int i = 0; i < 2 && offset + 4 <= block.size(); i++) {
+            offset += 4 + readBigEndian(block, offset, 4);
+        }
+        // width, height, color depth and the number of colors
+        offset += 16;
+        if(offset + 4 > block.size()) {
+            continue;
+        }
+
+        auto picture = block.mid(static_cast<int>(offset) + 4, readBigEndian(block, offset, 4));
+        if(picture.isEmpty()) {
+            continue;
+        }
+
+        if(pictureType == FRONT_COVER_TYPE) {
+            return picture;
+        }
+        if(cover.isEmpty()) {
+            cover = picture;
+        }
+    }
+
+    return cover;
+}
+
+// look for a child atom of the given type between start and end, contentStart and contentEnd are set to
+// the data inside it
+static bool findMp4Atom(QFile& file, qint64 start, qint64 end, QByteArray type,
+                        qint64* contentStart, qint64* contentEnd) {
+    auto offset = start;
+    while(offset + 8 <= end) {
+        file.seek(offset);
+        auto header = file.read(8);
+        if(header.size() < 8) {
+            return false;
+        }
+
+        qint64 atomSize = readBigEndian(header, 0, 4);
+        qint64 headerSize = 8;
+        if(atomSize == 1) {
+            // a 64 bit size follows the type
+            auto largeSize = file.read(8);
+            if(largeSize.size() < 8) {
+                return false;
+            }
+            atomSize = (static_cast<qint64>(readBigEndian(largeSize, 0, 4)) << 32) | readBigEndian(largeSize, 4, 4);
+            headerSize = 16;
+        } else if(atomSize == 0) {
+            // the atom runs until the end of its parent
+            atomSize = end - offset;
+        }
+
+        if(atomSize < headerSize || offset + atomSize > end) {
+            return false;
+        }
+
+        if(header.mid(4, 4) == type) {
+            *contentStart = offset + headerSize;
+            *contentEnd = offset + atomSize;
+            return true;
+        }
+
+        offset += atomSize;
+    }
+
+    return false;
+}
+
+static QByteArray readMp4Cover(QFile& file) {
+    qint64 start = 0;
+    qint64 end = file.size();
+
+    QList<QByteArray> atomPath = {"moov", "udta", "meta", "ilst", "covr", "data"};
+    for(auto &type : atomPath) {
+        if(!findMp4Atom(file, start, end, type, &start, &end)) {
+            return QByteArray();
+        }
+
+        // meta has a version and flags before its children
+        if(type == "meta") {
+            start += 4;
+        }
+    }
+
+    // the image comes after the value type and the locale
+    start += 8;
+    if(end - start <= 0 || end - start > MAX_TAG_SIZE) {
+        return QByteArray();
+    }
+
+    file.seek(start);
+    return file.read(end - start);
+}
+
+QByteArray Core::readEmbeddedCover(QString path) {
+    QFile file(path);
+    if(!file.open(QIODevice::ReadOnly)) {
+        return QByteArray();
+    }
+
+    qint64 tagEnd = 0;
+    auto cover = readId3Cover(file, &tagEnd);
+    if(cover.isEmpty()) {
+        cover = readFlacCover(file, tagEnd);
+    }
+    if(cover.isEmpty()) {
+        file.seek(4);
+        if(file.read(4) == "ftyp") {
+            cover = readMp4Cover(file);
+        }
+    }
+
+    return cover;
+}
+
+QString Core::extractEmbeddedCover(QString path) {
+    auto cover = Core::readEmbeddedCover(path);
+    if(cover.isEmpty()) {
+        return QString();
+    }
+
+    // the image format is recognized from the data, so the name only has to be unique for the audio file
+    auto coverDirectory = QDir(Core::getSettingPath() + "/covers").absolutePath();
+    Core::createPathIfNotExists(coverDirectory);
+    auto pathHash = QCryptographicHash::hash(path.toUtf8(), QCryptographicHash::Sha1).toHex();
+    auto coverPath = QDir(coverDirectory).filePath("embedded-" + QString::fromLatin1(pathHash));
+
+    QFile coverFile(coverPath);
+    if(!coverFile.open(QIODevice::WriteOnly | QIODevice::Truncate)) {
+        qWarning() << "Could not open " << coverPath << " for writing";
+        return QString();
+    }
+
+    coverFile.write(cover);
+    coverFile.close();
+    if(coverFile.error() != QFile::NoError) {
+        return QString();
+    }
+
+    return coverPath;
+}
@@ -0,0 +1,19 @@
+#ifndef NODOKANATIVE_EMBEDDEDCOVER_H
+#define NODOKANATIVE_EMBEDDEDCOVER_H
+
+#include <QString>
+#include <QByteArray>
+
+namespace Core {
+
+    // the picture tag of an audio file, vlc only reports these as attachment:// urls
+    // reads ID3v2 (mp3), FLAC and MP4 (m4a, m4b) tags, the front cover is preferred when there are several
+    // returns an empty array if the file has no picture
+    QByteArray readEmbeddedCover(QString path);
+
+    // write the embedded cover of path into the cover cache, returns the path of the image or an empty string
+    QString extractEmbeddedCover(QString path);
+}
+
+
+#endif //NODOKANATIVE_EMBEDDEDCOVER_H
@@ -0,0 +1,312 @@
+#include <QFile>
+#include <QFileInfo>
+#include <QJsonDocument>
+#include <QJsonObject>
+#include <QJsonArray>
+#include <QSettings>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QDebug>
+#include "Util.h"
+#include "LibraryExport.h"
+
+static QJsonValue dateToJson(QVariant value) {
+    auto dateTime = value.toDateTime();
+    if(value.isNull() || !dateTime.isValid()) {
+        return QJsonValue::Null;
+    }
+
+    return dateTime.toUTC().toString(Qt::ISODate);
+}
+
+static QVariant dateFromJson(QJsonValue value) {
+    auto dateTime = QDateTime::fromString(value.toString(), Qt::ISODate);
+    if(!dateTime.isValid()) {
+        return QVariant(QVariant::DateTime);
+    }
+
+    return dateTime;
+}
+
+static QJsonArray exportDirectories() {
+    QJsonArray directories;
+
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM directories")) {
+        qWarning() << "directory export query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return directories;
+    }
+
+    while(query.next()) {
+        QJsonObject directory;
+        directory["path"] = query.value("full_path").toString();
+        directory["enabled"] = query.value("enabled").toBool();
+        directories.append(directory);
+    }
+
+    return directories;
+}
+
+static QJsonArray exportAudiobooks(std::shared_ptr<ProxyManager> manager) {
+    QJsonArray audiobooks;
+
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM audiobooks ORDER BY id ASC")) {
+        qWarning() << "audiobook export query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return audiobooks;
+    }
+
+    while(query.next()) {
+        auto record = query.record();
+        auto audiobookProxy = manager->getAudiobookProxy(record);
+
+        QJsonArray files;
+        for(auto &file : audiobookProxy->getFilesForAudiobook()) {
+            // files that were never played have nothing worth carrying over
+            if(file->currentTimeNull() && file->getCompleteness() == 0) {
+                continue;
+            }
+
+            QJsonObject fileObject;
+            fileObject["path"] = file->path();
+            fileObject["currentTime"] = static_cast<double>(file->getCurrentTime());
+            fileObject["completeness"] = file->getCompleteness();
+            fileObject["lastPlayedAt"] = dateToJson(file->getLastPlayedAt());
+            files.append(fileObject);
+        }
+
+        QJsonObject audiobook;
+        audiobook["path"] = record.value("full_path").toString();
+        audiobook["name"] = record.value("name").toString();
+        audiobook["selectedFile"] = record.value("selected_file").isNull() ?
+                                    QJsonValue::Null : QJsonValue(record.value("selected_file").toString());
+        audiobook["playbackSpeed"] = record.value("playback_speed").isNull() ?
+                                     QJsonValue::Null : QJsonValue(record.value("playback_speed").toString());
+        audiobook["lastPlayedAt"] = dateToJson(record.value("last_played_at"));
+        audiobook["finishedAt"] = dateToJson(record.value("finished_at"));
+        audiobook["files"] = files;
+        audiobooks.append(audiobook);
+    }
+
+    return audiobooks;
+}
+
+bool Core::exportLibrary(std::shared_ptr<ProxyManager> manager, QString path) {
+    QJsonObject root;
+    root["version"] = LIBRARY_EXPORT_VERSION;
+    root["directories"] = exportDirectories();
+    root["audiobooks"] = exportAudiobooks(manager);
+
+    QFile file(path);
+    if(!file.open(QIODevice::WriteOnly | QIODevice::Truncate)) {
+        qWarning() << "Could not open " << path << " for writing";
+        return false;
+    }
+
+    file.write(QJsonDocument(root).toJson());
+    file.close();
+
+    return file.error() == QFile::NoError;
+}
+
+// the progress is kept in the per file settings, which are keyed by path, so it can be written
+// even when the file hasn't been scanned into the database yet
+static void importFileProgress(QJsonObject fileObject) {
+    auto path = fileObject["path"].toString();
+    QSettings fileSetting(Core::getUniqueSettingPath(path), QSettings::IniFormat);
+
+    fileSetting.setValue("currentTime", static_cast<long long>(fileObject["currentTime"].toDouble()));
+    fileSetting.setValue("completeness", fileObject["completeness"].toInt());
+
+    auto lastPlayedAt = dateFromJson(fileObject["lastPlayedAt"]);
+    if(!lastPlayedAt.isNull()) {
+        fileSetting.setValue("lastPlayedAt", lastPlayedAt);
+    }
+
+    fileSetting.sync();
+}
+
+static bool updateImportedAudiobook(std::shared_ptr<ProxyManager> manager,
+                                    QSqlRecord record,
+                                    QVariant selectedFile,
+                                    QVariant playbackSpeed,
+                                    QVariant lastPlayedAt,
+                                    QVariant finishedAt) {
+    auto audiobookProxy = manager->getAudiobookProxy(record);
+    audiobookProxy->updateCompletionStatus();
+
+    // this has to come after the completion status, which would stamp the finish time with the current time
+    QSqlQuery updateQuery;
+    updateQuery.prepare("UPDATE audiobooks SET selected_file = ?, playback_speed = ?, "
+                        "last_played_at = ?, finished_at = ? WHERE id = ?");
+    updateQuery.addBindValue(selectedFile);
+    updateQuery.addBindValue(playbackSpeed);
+    updateQuery.addBindValue(lastPlayedAt);
+    updateQuery.addBindValue(finishedAt);
+    updateQuery.addBindValue(record.value("id").toInt());
+    if(!updateQuery.exec()) {
+        qWarning() << "audiobook import update failed: "
+                   << updateQuery.lastError().driverText()
+                   << ", " << updateQuery.lastError().databaseText();
+        return false;
+    }
+
+    return true;
+}
+
+// returns true if the audiobook is already in the library
+static bool importAudiobook(std::shared_ptr<ProxyManager> manager, QJsonObject audiobook) {
+    auto selectedFile = audiobook["selectedFile"].isNull() ?
+                        QVariant(QVariant::String) : QVariant(audiobook["selectedFile"].toString());
+    auto playbackSpeed = audiobook["playbackSpeed"].isNull() ?
+                         QVariant(QVariant::String) : QVariant(audiobook["playbackSpeed"].toString());
+    auto lastPlayedAt = dateFromJson(audiobook["lastPlayedAt"]);
+    auto finishedAt = dateFromJson(audiobook["finishedAt"]);
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE full_path = ?");
+    query.addBindValue(audiobook["path"].toString());
+    if(!query.exec()) {
+        qWarning() << "audiobook import lookup failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return false;
+    }
+
+    if(query.next()) {
+        return updateImportedAudiobook(manager, query.record(), selectedFile, playbackSpeed, lastPlayedAt, finishedAt);
+    }
+
+    // its directory is still being scanned, keep the fields until the audiobook is registered
+    QSqlQuery pendingQuery;
+    pendingQuery.prepare("INSERT OR REPLACE INTO pending_imports "
+                         "(full_path, selected_file, playback_speed, last_played_at, finished_at) "
+                         "VALUES (?, ?, ?, ?, ?)");
+    pendingQuery.addBindValue(audiobook["path"].toString());
+    pendingQuery.addBindValue(selectedFile);
+    pendingQuery.addBindValue(playbackSpeed);
+    pendingQuery.addBindValue(lastPlayedAt);
+    pendingQuery.addBindValue(finishedAt);
+    if(!pendingQuery.exec()) {
+        qWarning() << "audiobook pending import insert failed: "
+                   << pendingQuery.lastError().driverText()
+                   << ", " << pendingQuery.lastError().databaseText();
+    }
+
+    return false;
+}
+
+void Core::applyPendingImport(std::shared_ptr<ProxyManager> manager, QSqlRecord audiobookRecord) {
+    auto path = audiobookRecord.value("full_path").toString();
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM pending_imports WHERE full_path = ?");
+    query.addBindValue(path);
+    if(!query.exec()) {
+        qWarning() << "audiobook pending import query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    if(!query.next()) {
+        return;
+    }
+
+    updateImportedAudiobook(manager, audiobookRecord,
+                            query.value("selected_file"),
+                            query.value("playback_speed"),
+                            query.value("last_played_at"),
+                            query.value("finished_at"));
+
+    QSqlQuery deleteQuery;
+    deleteQuery.prepare("DELETE FROM pending_imports WHERE full_path = ?");
+    deleteQuery.addBindValue(path);
+    if(!deleteQuery.exec()) {
+        qWarning() << "audiobook pending import delete failed: "
+                   << deleteQuery.lastError().driverText()
+                   << ", " << deleteQuery.lastError().databaseText();
+    }
+}
+
+bool Core::importLibrary(std::shared_ptr<ProxyManager> manager,
+                         Directory* directoryModel,
+                         QString path,
+                         LibraryImportSummary* summary,
+                         QString* errorMessage) {
+    QFile file(path);
+    if(!file.open(QIODevice::ReadOnly)) {
+        *errorMessage = "Could not open " + path;
+        return false;
+    }
+
+    QJsonParseError parseError;
+    auto document = QJsonDocument::fromJson(file.readAll(), &parseError);
+    if(document.isNull() || !document.isObject()) {
+        *errorMessage = "The file is not a library export: " + parseError.errorString();
+        return false;
+    }
+
+    auto root = document.object();
+    auto version = root["version"].toInt();
+    if(version < 1 || version > LIBRARY_EXPORT_VERSION) {
+        *errorMessage = "The file was exported by an unsupported version of Nodoka";
+        return false;
+    }
+
+    QStringList knownDirectories;
+    for(int i = 0; i < directoryModel->rowCount(); i++) {
+        knownDirectories << directoryModel->record(i).value("full_path").toString();
+    }
+
+    for(auto directoryValue : root["directories"].toArray()) {
+        auto directoryObject = directoryValue.toObject();
+        auto directoryPath = directoryObject["path"].toString();
+        if(!QFileInfo(directoryPath).isDir()) {
+            summary->missingPaths++;
+        }
+
+        if(!knownDirectories.contains(directoryPath)) {
+            directoryModel->addDirectory(directoryPath);
+            knownDirectories << directoryPath;
+            summary->directoriesAdded++;
+        }
+
+        if(!directoryObject.contains("enabled")) {
+            continue;
+        }
+
+        auto enabled = directoryObject["enabled"].toBool();
+        for(int i = 0; i < directoryModel->rowCount(); i++) {
+            auto index = directoryModel->index(i, 0);
+            if(directoryModel->record(i).value("full_path").toString() == directoryPath &&
+               directoryModel->isDirectoryEnabled(index) != enabled) {
+                directoryModel->setDirectoryEnabled(index, enabled);
+            }
+        }
+    }
+
+    for(auto audiobookValue : root["audiobooks"].toArray()) {
+        auto audiobook = audiobookValue.toObject();
+
+        for(auto fileValue : audiobook["files"].toArray()) {
+            auto fileObject = fileValue.toObject();
+            if(!QFileInfo(fileObject["path"].toString()).exists()) {
+                summary->missingPaths++;
+            }
+
+            importFileProgress(fileObject);
+            summary->filesUpdated++;
+        }
+
+        if(importAudiobook(manager, audiobook)) {
+            summary->audiobooksUpdated++;
+        }
+    }
+
+    return true;
+}
@@ -0,0 +1,39 @@
+#ifndef NODOKANATIVE_LIBRARYEXPORT_H
+#define NODOKANATIVE_LIBRARYEXPORT_H
+
+#include <QString>
+#include <QSqlRecord>
+#include <memory>
+#include <src/proxy-objects/ProxyManager.h>
+#include <src/model/Directory.h>
+
+// bump this when the layout of the exported file changes
+const static int LIBRARY_EXPORT_VERSION = 1;
+
+namespace Core {
+
+    struct LibraryImportSummary {
+        int directoriesAdded = 0;
+        int audiobooksUpdated = 0;
+        int filesUpdated = 0;
+        // paths in the file that don't exist on this machine, they are still imported
+        int missingPaths = 0;
+    };
+
+    // write the scanning directories, audiobooks and the progress of every file as json
+    bool exportLibrary(std::shared_ptr<ProxyManager> manager, QString path);
+
+    // read a file written by exportLibrary, importing the same file again only overwrites the progress
+    // returns false and sets errorMessage if the file can't be read
+    bool importLibrary(std::shared_ptr<ProxyManager> manager,
+                       Directory* directoryModel,
+                       QString path,
+                       LibraryImportSummary* summary,
+                       QString* errorMessage);
+
+    // apply the fields an import kept for this audiobook while its directory was being scanned
+    void applyPendingImport(std::shared_ptr<ProxyManager> manager, QSqlRecord audiobookRecord);
+}
+
+
+#endif //NODOKANATIVE_LIBRARYEXPORT_H
@@ -0,0 +1,100 @@
+#include <algorithm>
+#include <QFile>
+#include <QTextStream>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QDebug>
+#include "ListeningStats.h"
+
+static QString escapeCsvField(QString value) {
+    if(value.contains(',') || value.contains('"') || value.contains('\n')) {
+        value.replace("\"", "\"\"");
+        return "\"" + value + "\"";
+    }
+
+    return value;
+}
+
+std::vector<Core::AudiobookStats> Core::collectListeningStats(std::shared_ptr<ProxyManager> manager) {
+    std::vector<AudiobookStats> stats;
+
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM audiobooks ORDER BY id ASC")) {
+        qWarning() << "audiobook stats query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return stats;
+    }
+
+    while(query.next()) {
+        auto record = query.record();
+        auto audiobook = manager->getAudiobookProxy(record);
+
+        AudiobookStats currentStats;
+        currentStats.name = record.value("name").toString();
+        if(!record.value("title").isNull()) {
+            currentStats.name = record.value("title").toString();
+        }
+        currentStats.duration = std::max(0LL, audiobook->getDuration());
+        currentStats.completeness = audiobook->getCompleteness();
+        currentStats.lastPlayedAt = record.value("last_played_at").toDateTime();
+
+        // a finished file counts in full, otherwise only up to where it was left off
+        currentStats.listenedDuration = 0;
+        for(auto &file : audiobook->getFilesForAudiobook()) {
+            if(file->getCompleteness() >= 100 && file->getMediaDuration() > 0) {
+                currentStats.listenedDuration += file->getMediaDuration();
+            } else if(!file->currentTimeNull()) {
+                currentStats.listenedDuration += file->getCurrentTime();
+            }
+        }
+
+        stats.push_back(currentStats);
+    }
+
+    return stats;
+}
+
+Core::LibraryStats Core::summarizeListeningStats(const std::vector<AudiobookStats>& stats) {
+    LibraryStats libraryStats;
+    libraryStats.audiobookCount = static_cast<int>(stats.size());
+    libraryStats.finishedCount = 0;
+    libraryStats.listenedDuration = 0;
+
+    for(auto &currentStats : stats) {
+        if(currentStats.completeness >= 100) {
+            libraryStats.finishedCount++;
+        }
+        libraryStats.listenedDuration += currentStats.listenedDuration;
+    }
+
+    return libraryStats;
+}
+
+bool Core::exportListeningStats(const std::vector<AudiobookStats>& stats, QString path) {
+    QFile file(path);
+    if(!file.open(QIODevice::WriteOnly | QIODevice::Truncate | QIODevice::Text)) {
+        qWarning() << "Could not open " << path << " for writing";
+        return false;
+    }
+
+    QTextStream out(&file);
+    out.setCodec("UTF-8");
+    out << "Audiobook,Total Duration (s),Listened Duration (s),Percent Complete,Last Played\n";
+
+    for(auto &currentStats : stats) {
+        QString lastPlayed = "";
+        if(currentStats.lastPlayedAt.isValid()) {
+            lastPlayed = currentStats.lastPlayedAt.toLocalTime().toString(Qt::ISODate);
+        }
+
+        out << escapeCsvField(currentStats.name) << ","
+            << currentStats.duration / 1000 << ","
+            << currentStats.listenedDuration / 1000 << ","
+            << currentStats.completeness << ","
+            << lastPlayed << "\n";
+    }
+
+    file.close();
+    return file.error() == QFile::NoError;
+}
@@ -0,0 +1,37 @@
+#ifndef NODOKANATIVE_LISTENINGSTATS_H
+#define NODOKANATIVE_LISTENINGSTATS_H
+
+#include <QString>
+#include <QDateTime>
+#include <memory>
+#include <vector>
+#include <src/proxy-objects/ProxyManager.h>
+
+namespace Core {
+
+    struct AudiobookStats {
+        QString name;
+        long long duration;
+        long long listenedDuration;
+        int completeness;
+        QDateTime lastPlayedAt;
+    };
+
+    struct LibraryStats {
+        int audiobookCount;
+        int finishedCount;
+        long long listenedDuration;
+    };
+
+    // collect the listening progress of every audiobook in the library
+    std::vector<AudiobookStats> collectListeningStats(std::shared_ptr<ProxyManager> manager);
+
+    // totals over the whole library
+    LibraryStats summarizeListeningStats(const std::vector<AudiobookStats>& stats);
+
+    // write the stats as a csv file, returns false if the file couldn't be written
+    bool exportListeningStats(const std::vector<AudiobookStats>& stats, QString path);
+}
+
+
+#endif //NODOKANATIVE_LISTENINGSTATS_H
@@ -0,0 +1,129 @@
+#include <QMutex>
+#include <QMutexLocker>
+#include <QDir>
+#include <QDateTime>
+#include <QRegularExpression>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QDebug>
+#include <algorithm>
+#include <cstdio>
+#include "LogBuffer.h"
+
+static QMutex logMutex;
+static QStringList logLines;
+static QtMessageHandler previousHandler = nullptr;
+
+// titles shorter than this would replace ordinary words
+const static int MIN_REDACTED_TITLE_LENGTH = 3;
+
+static QString messageTypeName(QtMsgType type) {
+    switch(type) {
+        case QtDebugMsg:
+            return "debug";
+        case QtInfoMsg:
+            return "info";
+        case QtWarningMsg:
+            return "warning";
+        case QtCriticalMsg:
+            return "critical";
+        case QtFatalMsg:
+        default:
+            return "fatal";
+    }
+}
+
+static void bufferMessage(QtMsgType type, const QMessageLogContext& context, const QString& message) {
+    {
+        QMutexLocker locker(&logMutex);
+        logLines << QDateTime::currentDateTimeUtc().toString(Qt::ISODate) + " [" + messageTypeName(type) + "] " + message;
+        while(logLines.size() > LOG_BUFFER_LINES) {
+            logLines.removeFirst();
+        }
+    }
+
+    if(previousHandler != nullptr) {
+        previousHandler(type, context, message);
+    } else {
+        fprintf(stderr, "%s\n", qPrintable(qFormatLogMessage(type, context, message)));
+    }
+}
+
+void Core::installLogBuffer() {
+    previousHandler = qInstallMessageHandler(bufferMessage);
+}
+
+// the scanning directories and the audiobooks with their names, these are known even if they
+// haven't been loaded yet
+static void findLibraryStrings(QStringList* paths, QStringList* titles) {
+    QSqlQuery query;
+    if(query.exec("SELECT full_path FROM directories")) {
+        while(query.next()) {
+            *paths << query.value("full_path").toString();
+        }
+    } else {
+        qWarning() << "directory query failed: " << query.lastError().databaseText();
+    }
+
+    if(query.exec("SELECT full_path, name, title, display_name FROM audiobooks")) {
+        while(query.next()) {
+            *paths << query.value("full_path").toString();
+            *titles << query.value("name").toString()
+                    << query.value("title").toString()
+                    << query.value("display_name").toString();
+        }
+    } else {
+        qWarning() << "audiobook query failed: " << query.lastError().databaseText();
+    }
+}
+
+// the longest strings come first, so a file path goes as a whole rather than only its directory
+static void sortLongestFirst(QStringList& strings) {
+    std::sort(strings.begin(), strings.end(), [](const QString& first, const QString& second) {
+        return first.size() > second.size();
+    });
+}
+
+static void redactStrings(QString& line, const QStringList& strings, QString replacement) {
+    for(auto &string : strings) {
+        line.replace(string, replacement);
+        line.replace(QDir::toNativeSeparators(string), replacement);
+    }
+}
+
+QStringList Core::getRecentLogLines(std::shared_ptr<ProxyManager> manager) {
+    // windows drive paths, unix paths and quoted paths with spaces
+    static const QRegularExpression quotedPathPattern("\"(?:[A-Za-z]:)?[\\\\/][^\"]*\"");
+    static const QRegularExpression pathPattern("(?:[A-Za-z]:)?[\\\\/][^\\s,\"']+");
+
+    QStringList lines;
+    {
+        QMutexLocker locker(&logMutex);
+        lines = logLines;
+    }
+
+    QStringList knownPaths = manager->getKnownPaths();
+    QStringList knownTitles;
+    findLibraryStrings(&knownPaths, &knownTitles);
+    knownPaths.removeDuplicates();
+    knownPaths.removeAll(QString());
+    knownTitles.removeDuplicates();
+    knownTitles.erase(std::remove_if(knownTitles.begin(), knownTitles.end(), [](const QString& title) {
+        return title.trimmed().size() < MIN_REDACTED_TITLE_LENGTH;
+    }), knownTitles.end());
+    sortLongestFirst(knownPaths);
+    sortLongestFirst(knownTitles);
+
+    auto homePath = QDir::homePath();
+    for(auto &line : lines) {
+        // the paths we know about can contain spaces, which the patterns below would cut off
+        redactStrings(line, knownPaths, "<path>");
+        redactStrings(line, knownTitles, "<title>");
+        line.replace(homePath, "~");
+        line.replace(QDir::toNativeSeparators(homePath), "~");
+        line.replace(quotedPathPattern, "\"<path>\"");
+        line.replace(pathPattern, "<path>");
+    }
+
+    return lines;
+}
@@ -0,0 +1,22 @@
+#ifndef NODOKANATIVE_LOGBUFFER_H
+#define NODOKANATIVE_LOGBUFFER_H
+
+#include <QStringList>
+#include <memory>
+#include <src/proxy-objects/ProxyManager.h>
+
+// how many of the latest log lines are kept for the diagnostic report
+const static int LOG_BUFFER_LINES = 200;
+
+namespace Core {
+
+    // keep the latest log messages in memory, the messages are still printed as before
+    void installLogBuffer();
+
+    // the kept log lines, oldest first, with the paths of the library replaced by <path> and audiobook
+    // titles by <title>, anything else that looks like a file path is replaced by <path> as well
+    QStringList getRecentLogLines(std::shared_ptr<ProxyManager> manager);
+}
+
+
+#endif //NODOKANATIVE_LOGBUFFER_H
@@ -4,7 +4,11 @@
 
 #include "NodokaApp.h"
 #include <QItemSelection>
+#include <QSqlRecord>
+#include <QTimer>
 #include <src/core/tasks/InitialScanTask.h>
+#include <src/core/tasks/ChecksumTask.h>
+#include <src/core/AudiobookScan.h>
 #include "src/event-handler/PlayerEventHandler.h"
 #include "ScanPlayer.h"
 #include "DatabaseConnect.h"
@@ -34,7 +38,7 @@ Core::NodokaApp::NodokaApp(QObject* parent) : QObject(parent) {
     // initialize db backed models
     this->directoryModel = new Directory();
     this->audiobookFileModel = new AudiobookFile();
-    this->audiobookModel = new Audiobook(this->audiobookFileModel, this->proxyManager, this->scanPlayer);
+    this->audiobookModel = new Audiobook(this->audiobookFileModel, this->proxyManager, this->scanPlayer, this->setting);
 
     // model event handlers
     this->audiobookCollectionHandler = std::shared_ptr<AudiobookCollectionHandler>(
@@ -54,8 +58,13 @@ Core::NodokaApp::NodokaApp(QObject* parent) : QObject(parent) {
     );
 
     // initialize event handlers
-    this->directoryHandler = new DirectoryHandler(this->audiobookModel, this->audiobookFileModel);
-    this->playerEventHandler = new PlayerEventHandler(this->player, this->mainWindow);
+    this->directoryHandler = new DirectoryHandler(this->audiobookModel, this->audiobookFileModel, this->setting);
+    this->playerEventHandler = new PlayerEventHandler(this->player, this->setting, this->mainWindow);
+    this->directoryWatcher = new DirectoryWatcher(this->directoryModel,
+                                                  this->audiobookModel,
+                                                  this->setting,
+                                                  this->proxyManager,
+                                                  this);
 
 
     this->setup();
@@ -65,9 +74,65 @@ void Core::NodokaApp::start() {
     mainWindow->show();
 }
 
+void Core::NodokaApp::addScanningDirectory(QString path) {
+    auto directoryPath = QDir::cleanPath(QFileInfo(path).absoluteFilePath());
+
+    // the directory model emits directoryAdded, which starts the scan
+    this->directoryModel->addDirectory(directoryPath);
+}
+
+void Core::NodokaApp::openPath(QString path) {
+    QFileInfo pathInfo(path);
+    auto fullPath = QDir::cleanPath(pathInfo.absoluteFilePath());
+    auto audiobookPath = pathInfo.isDir() ? fullPath : QDir::cleanPath(pathInfo.absolutePath());
+
+    auto findAudiobookId = [audiobookPath]() -> int {
+        QSqlQuery query;
+        query.prepare("SELECT id FROM audiobooks WHERE full_path = ?");
+        query.addBindValue(audiobookPath);
+        if(query.exec() && query.next()) {
+            return query.value("id").toInt();
+        }
+
+        return -1;
+    };
+
+    // add the audiobook first if it isn't part of the library yet
+    auto audiobookId = findAudiobookId();
+    if(audiobookId == -1) {
+        this->audiobookCollectionHandler->directoryAdded(audiobookPath);
+        audiobookId = findAudiobookId();
+    }
+
+    if(audiobookId == -1) {
+        qWarning() << "Could not open " << path;
+        return;
+    }
+
+    this->mainWindow->selectAudiobook(audiobookId);
+
+    if(pathInfo.isFile()) {
+        QSqlQuery query;
+        query.prepare("SELECT * FROM audiobook_file WHERE full_path = ?");
+        query.addBindValue(fullPath);
+        if(!query.exec() || !query.next()) {
+            qWarning() << "Could not find " << path << " in the audiobook";
+            return;
+        }
+
+        this->player->releaseMedia();
+        this->player->loadMedia(query.record());
+        this->player->play();
+    }
+}
+
 void Core::NodokaApp::setup() {
-    // set the stylesheet
-    this->mainWindow->setStyleSheet(MAINWINDOW_STYLE);
+    // set the stylesheet, again whenever the accent color changes
+    auto applyStyle = [this](QString accentColor) {
+        this->mainWindow->setStyleSheet(QString(MAINWINDOW_STYLE).replace("@accent", accentColor));
+    };
+    applyStyle(this->setting->getAccentColor());
+    connect(this->setting, &Setting::accentColorUpdated, applyStyle);
 
     // set up the listeners for the directory add/remove
     connect(this->directoryModel, &Directory::directoryAdded,
@@ -76,20 +141,65 @@ void Core::NodokaApp::setup() {
             this->directoryHandler, &DirectoryHandler::handleDirectoryRemoved);
     connect(this->directoryModel, &Directory::directoryRescan,
             this->directoryHandler, &DirectoryHandler::handleDirectoryRescan);
+    connect(this->directoryModel, &Directory::directoryEnabledChanged,
+            this->directoryHandler, &DirectoryHandler::handleDirectoryEnabledChanged);
+    connect(this->directoryModel, &Directory::directoryRescanAll,
+            this->directoryHandler, &DirectoryHandler::handleDirectoryRescanAll);
+    connect(this->directoryHandler, &DirectoryHandler::rescanAllProgressed,
+            this->mainWindow, &MainWindow::updateRescanProgress);
+
+    // keep the watched paths in sync with the scanning directories
+    connect(this->directoryModel, &Directory::directoryAdded,
+            this->directoryWatcher, &DirectoryWatcher::handleDirectoryAdded);
+    connect(this->directoryModel, &Directory::directoryRemove,
+            this->directoryWatcher, &DirectoryWatcher::handleDirectoryRemoved);
+    connect(this->setting, &Setting::watchDirectoriesUpdated,
+            this->directoryWatcher, &DirectoryWatcher::setEnabled);
+    this->directoryWatcher->setEnabled(this->setting->getWatchDirectories());
 
 
     // we need to register this metatype before using it in signal/slot pattern
     qRegisterMetaType<std::shared_ptr<AudiobookFileProxy>>("std::shared_ptr<AudiobookFileProxy>");
     qRegisterMetaType<QItemSelection>("QItemSelection");
+    qRegisterMetaType<QSqlRecord>("QSqlRecord");
 
     // set up the events between playerEvents and mainWindow
     connect(this->playerEventHandler, &PlayerEventHandler::notifyPlayerState,
             this->mainWindow, &MainWindow::playerStateUpdated);
+    connect(this->playerEventHandler, &PlayerEventHandler::notifyPlayerLoading,
+            this->mainWindow, &MainWindow::playerLoadingUpdated);
     connect(this->playerEventHandler, &PlayerEventHandler::notifyPlayerTime,
             this->mainWindow, &MainWindow::playerTimeUpdated);
     connect(this->playerEventHandler, &PlayerEventHandler::notifyMediaParsed,
             this->mainWindow, &MainWindow::audiobookFileStateUpdated);
 
+    // the scanner doesn't know about the settings, so it gets its own copy of the extension lists
+    Core::setFileExtensionOverrides(this->setting->getExtraFileExtensions(),
+                                    this->setting->getIgnoredFileExtensions());
+    connect(this->setting, &Setting::fileExtensionsUpdated, [this]() {
+        Core::setFileExtensionOverrides(this->setting->getExtraFileExtensions(),
+                                        this->setting->getIgnoredFileExtensions());
+    });
+
+    // the progress is only saved every few seconds while playing, so the latest position is saved on the way out
+    connect(QCoreApplication::instance(), &QCoreApplication::aboutToQuit,
+            this->playerEventHandler, &PlayerEventHandler::flushProgress);
+
+    connect(this->setting, &Setting::tickIntervalUpdated,
+            this->player, &ConcretePlayer::setTickInterval);
+
+    // checksums are written per file, so running them in parallel doesn't change the results
+    ChecksumTask::threadPoolInstance.setMaxThreadCount(this->setting->getScanWorkers());
+    // audiobooks scanned before the option was on are still one per disc
+    connect(this->setting, &Setting::combineDiscFoldersUpdated, [this](bool combineDiscFolders) {
+        if(combineDiscFolders) {
+            this->audiobookModel->combineDiscAudiobooks();
+        }
+    });
+    connect(this->setting, &Setting::scanWorkersUpdated, [](int workers) {
+        ChecksumTask::threadPoolInstance.setMaxThreadCount(workers);
+    });
+
     this->scanThread = new QThreadPool();
 
     std::vector<std::shared_ptr<AudiobookProxy>> list;
@@ -100,6 +210,11 @@ void Core::NodokaApp::setup() {
     }
 
     this->scanThread->start(new InitialScanTask(this->scanPlayer, list));
+
+    // queued so the window is drawn first, the rescan itself runs in the background and shows its progress
+    if(this->setting->getScanOnStartup()) {
+        QTimer::singleShot(0, this->directoryModel, &Directory::rescanAll);
+    }
 }
 
 Core::NodokaApp::~NodokaApp() {
@@ -16,6 +16,7 @@
 #include <src/model/AudiobookFile.h>
 #include <src/ui-element/MainWindow.h>
 #include <src/event-handler/DirectoryHandler.h>
+#include <src/event-handler/DirectoryWatcher.h>
 #include <src/event-handler/AudiobookCollectionHandler.h>
 
 namespace Core {
@@ -29,6 +30,7 @@ namespace Core {
         Directory* directoryModel;
         MainWindow* mainWindow;
         DirectoryHandler* directoryHandler;
+        DirectoryWatcher* directoryWatcher;
         Audiobook* audiobookModel;
         AudiobookFile* audiobookFileModel;
         Setting* setting;
@@ -46,17 +48,22 @@ namespace Core {
         NodokaApp(QObject *parent = 0);
         ~NodokaApp();
         void start();
+
+        // actions requested from the command line
+        void addScanningDirectory(QString path);
+        void openPath(QString path);
     };
 
 }
 
+// @accent is replaced with the accent color from the settings
 const static char* MAINWINDOW_STYLE = "QMainWindow {"
         "font-family: \"Source Sans Pro\";"
         "font-size: 14px;"
         "}"
         ""
         "QWidget#topHorizontalWidget {"
-        "background-color: #FEDB53;"
+        "background-color: @accent;"
         "}"
         ""
         "QWidget#playerVerticalWidget {"
@@ -169,14 +176,14 @@ const static char* MAINWINDOW_STYLE = "QMainWindow {"
         "}"
         ""
         "QSlider#progressSlider:sub-page{"
-        "background-color: #FEDB53;"
+        "background-color: @accent;"
         "margin-left: 15px;"
         "border-top-left-radius: 7px;"
         "border-bottom-left-radius: 7px;"
         "}"
         ""
         "QSlider#volumeSlider:sub-page{"
-        "background-color: #FEDB53;"
+        "background-color: @accent;"
         "border-top-left-radius: 7px;"
         "border-bottom-left-radius: 7px;"
         "}"
@@ -194,7 +201,7 @@ const static char* MAINWINDOW_STYLE = "QMainWindow {"
         "}"
         ""
         "QMenu:selected {"
-        "background-color:#FEDB53;"
+        "background-color:@accent;"
         "color: #000;"
         "}"
 
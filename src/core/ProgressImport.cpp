@@ -0,0 +1,218 @@
+#include <QFile>
+#include <QSet>
+#include <QTextStream>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QDebug>
+#include <QRegularExpression>
+#include <algorithm>
+#include "Util.h"
+#include "ProgressImport.h"
+
+// split one line into its fields, quoted fields may contain commas and "" for a quote
+static QStringList splitCsvLine(const QString& line) {
+    QStringList fields;
+    QString field;
+    bool inQuotes = false;
+
+    for(int i = 0; i < line.size(); i++) {
+        auto character = line[i];
+
+        if(inQuotes) {
+            if(character == '"' && i + 1 < line.size() && line[i + 1] == '"') {
+                field += '"';
+                i++;
+            } else if(character == '"') {
+                inQuotes = false;
+            } else {
+                field += character;
+            }
+        } else if(character == '"') {
+            inQuotes = true;
+        } else if(character == ',') {
+            fields << field.trimmed();
+            field.clear();
+        } else {
+            field += character;
+        }
+    }
+    fields << field.trimmed();
+
+    return fields;
+}
+
+static QSet<QString> titleWords(const QString& title) {
+    static const QRegularExpression separatorPattern("[^\\w]|_");
+
+    auto words = title.toLower().split(separatorPattern, QString::SkipEmptyParts);
+    return QSet<QString>::fromList(words);
+}
+
+double Core::titleSimilarity(const QString& first, const QString& second) {
+    auto firstWords = titleWords(first);
+    auto secondWords = titleWords(second);
+    if(firstWords.isEmpty() || secondWords.isEmpty()) {
+        return 0;
+    }
+
+    auto commonWords = QSet<QString>(firstWords).intersect(secondWords);
+    return 2.0 * commonWords.size() / (firstWords.size() + secondWords.size());
+}
+
+static void findBestMatch(Core::ProgressImportMatch* match) {
+    QSqlQuery query;
+    if(!query.exec("SELECT id, name, title, display_name FROM audiobooks")) {
+        qWarning() << "audiobook list query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    double bestSimilarity = 0;
+    while(query.next()) {
+        // the file may use the tag title, the folder name or the name given in Nodoka, whichever is closer counts
+        auto similarity = std::max({Core::titleSimilarity(match->title, query.value("name").toString()),
+                                    Core::titleSimilarity(match->title, query.value("title").toString()),
+                                    Core::titleSimilarity(match->title, query.value("display_name").toString())});
+
+        if(similarity >= MIN_TITLE_SIMILARITY && similarity > bestSimilarity) {
+            bestSimilarity = similarity;
+            match->audiobookId = query.value("id").toInt();
+            match->audiobookName = query.value("display_name").isNull() ?
+                                   query.value("name").toString() : query.value("display_name").toString();
+        }
+    }
+}
+
+// files before the position are marked as listened, the file the position falls into gets the rest of it
+static void applyProgress(std::shared_ptr<ProxyManager> manager, const Core::ProgressImportMatch& match) {
+    QSqlQuery audiobookQuery;
+    audiobookQuery.prepare("SELECT * FROM audiobooks WHERE id = ?");
+    audiobookQuery.addBindValue(match.audiobookId);
+    if(!audiobookQuery.exec() || !audiobookQuery.next()) {
+        qWarning() << "audiobook lookup failed: " << audiobookQuery.lastError().databaseText();
+        return;
+    }
+    auto audiobook = manager->getAudiobookProxy(audiobookQuery.record());
+
+    if(match.completeness >= 100) {
+        audiobook->markAsRead();
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(match.audiobookId);
+    if(!query.exec()) {
+        qWarning() << "audiobook file list query failed: " << query.lastError().databaseText();
+        return;
+    }
+
+    std::vector<std::shared_ptr<AudiobookFileProxy>> files;
+    while(query.next()) {
+        files.push_back(manager->getAudiobookFileProxy(query.record()));
+    }
+    if(files.empty()) {
+        return;
+    }
+
+    auto remaining = match.position;
+    auto target = files.back();
+    for(auto &file : files) {
+        // without a duration there is no way to tell how far into the audiobook the file is
+        auto duration = file->getMediaDuration();
+        if(file == files.back() || duration <= 0 || remaining < duration) {
+            target = file;
+            break;
+        }
+
+        file->markAsRead();
+        remaining -= duration;
+    }
+    target->saveCurrentTime(remaining);
+
+    QSqlQuery updateQuery;
+    updateQuery.prepare("UPDATE audiobooks SET selected_file = ? WHERE id = ?");
+    updateQuery.addBindValue(target->path());
+    updateQuery.addBindValue(match.audiobookId);
+    if(!updateQuery.exec()) {
+        qWarning() << "audiobook selected file update failed: "
+                   << updateQuery.lastError().driverText()
+                   << ", " << updateQuery.lastError().databaseText();
+    }
+
+    audiobook->updateCompletionStatus();
+}
+
+bool Core::importProgressCsv(std::shared_ptr<ProxyManager> manager,
+                             QString path,
+                             bool dryRun,
+                             std::vector<ProgressImportMatch>* plan,
+                             QString* errorMessage) {
+    QFile file(path);
+    if(!file.open(QIODevice::ReadOnly | QIODevice::Text)) {
+        *errorMessage = "Could not open " + path;
+        return false;
+    }
+
+    QTextStream in(&file);
+    in.setCodec("UTF-8");
+
+    plan->clear();
+    int lineNumber = 0;
+    bool isFirstRow = true;
+    while(!in.atEnd()) {
+        auto line = in.readLine();
+        lineNumber++;
+        if(line.trimmed().isEmpty()) {
+            continue;
+        }
+
+        auto fields = splitCsvLine(line);
+        auto isHeaderCandidate = isFirstRow;
+        isFirstRow = false;
+
+        ProgressImportMatch match;
+        match.line = lineNumber;
+        match.title = fields.value(0);
+        match.completeness = -1;
+        match.audiobookId = -1;
+
+        bool ok = false;
+        match.position = Core::parseTimeString(fields.value(1), &ok);
+        if(!ok) {
+            // a header row, it is only skipped when it comes first
+            if(isHeaderCandidate) {
+                continue;
+            }
+            match.position = -1;
+        }
+
+        if(!fields.value(2).isEmpty()) {
+            match.completeness = qBound(0, fields.value(2).remove('%').toInt(), 100);
+        }
+
+        if(match.position != -1 && !match.title.isEmpty()) {
+            findBestMatch(&match);
+        }
+
+        plan->push_back(match);
+    }
+
+    if(plan->empty()) {
+        *errorMessage = "The file doesn't have any rows with a title and a position";
+        return false;
+    }
+
+    if(dryRun) {
+        return true;
+    }
+
+    for(auto &match : *plan) {
+        if(match.audiobookId != -1) {
+            applyProgress(manager, match);
+        }
+    }
+
+    return true;
+}
@@ -0,0 +1,43 @@
+#ifndef NODOKANATIVE_PROGRESSIMPORT_H
+#define NODOKANATIVE_PROGRESSIMPORT_H
+
+#include <QString>
+#include <memory>
+#include <vector>
+#include <src/proxy-objects/ProxyManager.h>
+
+// how alike a title in the file and an audiobook name have to be before they are matched
+const static double MIN_TITLE_SIMILARITY = 0.6;
+
+namespace Core {
+
+    struct ProgressImportMatch {
+        // line in the file, starting at 1
+        int line;
+        QString title;
+        // position in the whole audiobook, -1 if the row couldn't be read
+        long long position;
+        // percent listened, -1 if the row doesn't have one
+        int completeness;
+        // -1 if no audiobook matched
+        int audiobookId;
+        QString audiobookName;
+    };
+
+    // how alike two titles are, from 0 for no word in common to 1 for the same words
+    // case and punctuation are ignored, so "The Great Gatsby" matches "the_great_gatsby"
+    double titleSimilarity(const QString& first, const QString& second);
+
+    // read "title,position[,completeness]" rows exported by another player and match every row to the
+    // audiobook with the most similar name, the position is h:mm:ss or seconds into the whole audiobook
+    // with dryRun only the plan is filled in, otherwise the progress of every matched audiobook is set as well
+    // returns false and sets errorMessage if the file can't be read
+    bool importProgressCsv(std::shared_ptr<ProxyManager> manager,
+                           QString path,
+                           bool dryRun,
+                           std::vector<ProgressImportMatch>* plan,
+                           QString* errorMessage);
+}
+
+
+#endif //NODOKANATIVE_PROGRESSIMPORT_H
@@ -7,8 +7,52 @@
 #include <include/vlc/vlc.h>
 #include <src/simple-lib/ThreadPool.h>
 #include <QThread>
+#include <QFileInfo>
+#include <QUrl>
+#include <QMap>
+#include <QElapsedTimer>
+#include <src/core/Util.h>
+#include <src/core/EmbeddedCover.h>
 #include "ScanPlayer.h"
 
+// read a single meta entry from a parsed media item, returns an empty string if it isn't set
+static QString readMeta(libvlc_media_t* mediaItem, libvlc_meta_t meta) {
+    char* value = libvlc_media_get_meta(mediaItem, meta);
+    if(value == nullptr) {
+        return QString();
+    }
+
+    QString result = QString::fromUtf8(value);
+    libvlc_free(value);
+
+    return result;
+}
+
+// turn a vlc fourcc into the name people know the codec by
+static QString codecName(uint32_t fourcc) {
+    QByteArray bytes;
+    for(int i = 0; i < 4; i++) {
+        bytes.append(static_cast<char>((fourcc >> (8 * i)) & 0xff));
+    }
+    auto fourccString = QString::fromLatin1(bytes).trimmed().toLower();
+
+    static const QMap<QString, QString> knownCodecs = {
+            {"mpga", "MP3"},
+            {"mp3", "MP3"},
+            {"mp4a", "AAC"},
+            {"flac", "FLAC"},
+            {"vorb", "Vorbis"},
+            {"opus", "Opus"},
+            {"alac", "ALAC"},
+            {"wma1", "WMA"},
+            {"wma2", "WMA"},
+            {"araw", "PCM"},
+            {"s16l", "PCM"}
+    };
+
+    return knownCodecs.value(fourccString, fourccString.toUpper());
+}
+
 Core::ScanPlayer::ScanPlayer() {
     /* Load the VLC engine */
     this->inst = libvlc_new(0, NULL);
@@ -24,6 +68,8 @@ Core::ScanPlayer::ScanPlayer() {
 
     this->threadPool = std::unique_ptr<ThreadPool>(new ThreadPool(1));
     this->hasScanFinished = true;
+    this->filesTotal = 0;
+    this->filesDone = 0;
 }
 
 void Core::ScanPlayer::addAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
@@ -32,6 +78,8 @@ void Core::ScanPlayer::addAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
     for(int i = 0; i < fileList.size(); i++) {
         this->fileQueue.push(fileList[i]);
     }
+    this->filesTotal += static_cast<int>(fileList.size());
+    emit this->scanProgressUpdated(this->filesDone, this->filesTotal);
     this->mutex.unlock();
 
     this->startScanTask(audiobook);
@@ -39,12 +87,14 @@ void Core::ScanPlayer::addAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
 
 void Core::ScanPlayer::addAudiobookFile(std::shared_ptr<AudiobookFileProxy> file) {
     // don't need to call this function on already scanned items
-    if(file->getMediaDuration() > 0) {
+    if(file->getMediaDuration() > 0 && file->isMetadataScanned()) {
         return;
     }
 
     this->mutex.lock();
     this->fileQueue.push(file);
+    this->filesTotal++;
+    emit this->scanProgressUpdated(this->filesDone, this->filesTotal);
     this->mutex.unlock();
 
     this->startScanTask(nullptr);
@@ -56,6 +106,15 @@ void Core::ScanPlayer::startScanTask(std::shared_ptr<AudiobookProxy> audiobook)
 }
 
 
+void Core::ScanPlayer::skipUnreadableFile(std::shared_ptr<AudiobookFileProxy> file) {
+    file->setScanFailed(true);
+    this->problemFiles << file->path();
+
+    this->fileQueue.pop();
+    this->filesDone++;
+    emit this->scanProgressUpdated(this->filesDone, this->filesTotal);
+}
+
 void Core::ScanPlayer::performScan() {
     this->mutex.lock();
 
@@ -68,14 +127,19 @@ void Core::ScanPlayer::performScan() {
         auto path =  element->path();
         auto currentFile = std::unique_ptr<QFile>(new QFile(path));
 
+        QElapsedTimer timer;
+        timer.start();
+
         if(!currentFile->open(QIODevice::ReadWrite)) {
             qDebug() << "QFILE FAILED!: " << path;
-            return;
+            this->skipUnreadableFile(element);
+            continue;
         }
 
         this->mediaItem = libvlc_media_new_fd(this->inst, currentFile->handle());
         if(this->mediaItem == NULL) {
-            return;
+            this->skipUnreadableFile(element);
+            continue;
         }
 
         libvlc_media_player_set_media(this->mediaPlayer, this->mediaItem);
@@ -94,10 +158,62 @@ void Core::ScanPlayer::performScan() {
                                         long long duration = libvlc_media_get_duration(player->mediaItem);
                                         if(duration > 0) {
                                             player->currentlyScanning->setMediaDuration(duration);
+                                            player->currentlyScanning->setScanFailed(false);
                                         } else {
                                             qWarning() << "performScan() failed for: " << player->currentlyScanning->path();
+                                            player->currentlyScanning->setScanFailed(true);
+                                            player->problemFiles << player->currentlyScanning->path();
                                         }
 
+                                        // vlc falls back to the input name when there is no title tag,
+                                        // which is not useful since we open files by descriptor
+                                        auto title = readMeta(player->mediaItem, libvlc_meta_Title);
+                                        auto fileName = QFileInfo(player->currentlyScanning->path()).fileName();
+                                        if(title.startsWith("fd://") || title == fileName) {
+                                            title = QString();
+                                        }
+
+                                        // track numbers can be written as "3/12"
+                                        auto trackString = readMeta(player->mediaItem, libvlc_meta_TrackNumber);
+                                        int trackNumber = trackString.section('/', 0, 0).trimmed().toInt();
+
+                                        player->currentlyScanning->setMediaTags(
+                                                title,
+                                                readMeta(player->mediaItem, libvlc_meta_Artist),
+                                                readMeta(player->mediaItem, libvlc_meta_Album),
+                                                trackNumber);
+
+                                        // vlc only points at embedded cover art with an attachment:// url,
+                                        // so the picture is read from the tag ourselves
+                                        QUrl artworkUrl(readMeta(player->mediaItem, libvlc_meta_ArtworkURL));
+                                        if(artworkUrl.isLocalFile()) {
+                                            player->currentlyScanning->setArtworkPath(artworkUrl.toLocalFile());
+                                        } else if(artworkUrl.scheme() == "attachment") {
+                                            player->currentlyScanning->setArtworkPath(
+                                                    Core::extractEmbeddedCover(player->currentlyScanning->path()));
+                                        } else {
+                                            player->currentlyScanning->setArtworkPath(QString());
+                                        }
+
+                                        // the first audio track describes the file well enough
+                                        QString codec = "";
+                                        int bitrate = 0;
+                                        int sampleRate = 0;
+                                        libvlc_media_track_t** tracks;
+                                        unsigned trackCount = libvlc_media_tracks_get(player->mediaItem, &tracks);
+                                        for(unsigned i = 0; i < trackCount; i++) {
+                                            if(tracks[i]->i_type == libvlc_track_audio) {
+                                                codec = codecName(tracks[i]->i_codec);
+                                                bitrate = static_cast<int>(tracks[i]->i_bitrate / 1000);
+                                                sampleRate = static_cast<int>(tracks[i]->audio->i_rate);
+                                                break;
+                                            }
+                                        }
+                                        if(trackCount > 0) {
+                                            libvlc_media_tracks_release(tracks, trackCount);
+                                        }
+                                        player->currentlyScanning->setCodecInfo(codec, bitrate, sampleRate);
+
                                         player->hasScanFinished = true;
                                     });
                                 }
@@ -110,8 +226,20 @@ void Core::ScanPlayer::performScan() {
         }
 
         libvlc_media_player_stop(this->mediaPlayer);
+        Core::logTiming("scan_media_properties", path, currentFile->size(), timer.elapsed());
 
         this->fileQueue.pop();
+        this->filesDone++;
+        emit this->scanProgressUpdated(this->filesDone, this->filesTotal);
+    }
+
+    this->filesTotal = 0;
+    this->filesDone = 0;
+    emit this->scanProgressUpdated(0, 0);
+
+    if(!this->problemFiles.isEmpty()) {
+        emit this->problemFilesFound(this->problemFiles);
+        this->problemFiles.clear();
     }
 
     this->mutex.unlock();
@@ -15,7 +15,9 @@
 #include <src/simple-lib/ThreadPool.h>
 
 namespace Core {
-    class ScanPlayer {
+    class ScanPlayer : public QObject {
+        Q_OBJECT
+
     private:
         QThreadPool scanThread;
         QMutex mutex;
@@ -26,6 +28,16 @@ namespace Core {
         // status variables
         bool hasScanFinished;
 
+        // files queued and scanned since the queue was last empty
+        int filesTotal;
+        int filesDone;
+
+        // files that couldn't be read since the queue was last empty
+        QStringList problemFiles;
+
+        // flag a file that can't even be opened and move on to the next one, the mutex has to be held
+        void skipUnreadableFile(std::shared_ptr<AudiobookFileProxy> file);
+
         // internal function to start the scan task in another thread
         void startScanTask(std::shared_ptr<AudiobookProxy> audiobook = nullptr);
 
@@ -41,6 +53,13 @@ namespace Core {
         void retrieveScanResults();
         void addAudiobook(std::shared_ptr<AudiobookProxy> audiobook);
         void addAudiobookFile(std::shared_ptr<AudiobookFileProxy> file);
+
+    signals:
+        // emitted from the scanning thread, both are 0 once the queue is empty
+        void scanProgressUpdated(int filesDone, int filesTotal);
+
+        // emitted once the queue is empty, with the files that couldn't be opened or had no duration
+        void problemFilesFound(QStringList paths);
     };
 }
 
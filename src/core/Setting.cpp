@@ -2,10 +2,29 @@
 // Created by mistlight on 1/8/17.
 //
 
+#include <cmath>
+#include <QThread>
+#include <QRegularExpression>
+#include <QDir>
+#include <QFile>
+#include <QFileInfo>
 #include "Setting.h"
+#include "Util.h"
+
+const static int MAXIMUM_SPEED_PRESETS = 6;
 
 Core::Setting::Setting() {
-    this->setting = new QSettings("nodoka.ini", QSettings::IniFormat);
+    // the settings hold ids from the database, so they live next to it in the data directory
+    auto settingDirectory = Core::getSettingPath();
+    Core::createPathIfNotExists(settingDirectory);
+    auto settingFile = QDir(settingDirectory).filePath("nodoka.ini");
+
+    // older versions kept the file in the working directory, a separate library starts with its own settings
+    if(!Core::isCustomSettingPath() && !QFileInfo::exists(settingFile) && QFileInfo::exists("nodoka.ini")) {
+        QFile::copy("nodoka.ini", settingFile);
+    }
+
+    this->setting = new QSettings(settingFile, QSettings::IniFormat);
 }
 
 void Core::Setting::setVolume(int volume) {
@@ -22,6 +41,14 @@ int Core::Setting::getVolume() {
     return this->setting->value("volume", 100).toInt();
 }
 
+int Core::Setting::getDefaultVolume() {
+    return this->setting->value("default_volume", 0).toInt();
+}
+
+void Core::Setting::setDefaultVolume(int volume) {
+    this->setting->setValue("default_volume", volume);
+}
+
 int Core::Setting::getCurrentAudiobookId() {
     if(!this->setting->contains("audiobook_id")) {
         return -1;
@@ -38,5 +65,389 @@ void Core::Setting::setSpeed(QString newSpeed) {
     this->setting->setValue("speed", newSpeed);
 }
 
+QStringList Core::Setting::getSpeedPresets() {
+    auto presets = this->setting->value("speed_presets").toStringList();
+    if(presets.isEmpty()) {
+        presets << "0.5" << "0.75" << "1" << "1.25" << "1.5" << "2";
+    }
+
+    return presets;
+}
+
+bool Core::Setting::setSpeedPresets(QStringList presets) {
+    if(presets.size() > MAXIMUM_SPEED_PRESETS) {
+        return false;
+    }
+
+    QStringList normalizedPresets;
+    for(auto &preset : presets) {
+        bool isNumber = false;
+        auto speed = preset.trimmed().toDouble(&isNumber);
+        if(!isNumber || speed < MIN_SPEED || speed > MAX_SPEED) {
+            return false;
+        }
+
+        // presets go in steps of 0.05
+        speed = std::round(speed * 20) / 20.0;
+        normalizedPresets << QString::number(speed);
+    }
+
+    this->setting->setValue("speed_presets", normalizedPresets);
+    emit this->speedPresetsUpdated();
+
+    return true;
+}
+
+QString Core::Setting::getAccentColor() {
+    return this->setting->value("accent_color", DEFAULT_ACCENT_COLOR).toString();
+}
+
+bool Core::Setting::setAccentColor(QString color) {
+    QString accentColor = DEFAULT_ACCENT_COLOR;
+
+    if(!color.trimmed().isEmpty()) {
+        bool ok = false;
+        auto parsedColor = Core::parseHexColor(color, &ok);
+        if(!ok || Core::contrastRatio(parsedColor, Qt::black) < MIN_ACCENT_CONTRAST) {
+            return false;
+        }
+        accentColor = parsedColor.name().toUpper();
+    }
+
+    this->setting->setValue("accent_color", accentColor);
+    emit this->accentColorUpdated(accentColor);
+
+    return true;
+}
+
+static bool validateExtensions(QStringList extensions) {
+    static const QRegularExpression extensionPattern("^[a-z0-9]+$");
+
+    for(auto &extension : extensions) {
+        if(!extensionPattern.match(extension).hasMatch()) {
+            return false;
+        }
+    }
+
+    return true;
+}
+
+QStringList Core::Setting::getExtraFileExtensions() {
+    return this->setting->value("extra_file_extensions").toStringList();
+}
+
+QStringList Core::Setting::getIgnoredFileExtensions() {
+    return this->setting->value("ignored_file_extensions").toStringList();
+}
+
+bool Core::Setting::setExtraFileExtensions(QStringList extensions) {
+    if(!validateExtensions(extensions)) {
+        return false;
+    }
+
+    this->setting->setValue("extra_file_extensions", extensions);
+    emit this->fileExtensionsUpdated();
+
+    return true;
+}
+
+bool Core::Setting::setIgnoredFileExtensions(QStringList extensions) {
+    if(!validateExtensions(extensions)) {
+        return false;
+    }
+
+    this->setting->setValue("ignored_file_extensions", extensions);
+    emit this->fileExtensionsUpdated();
+
+    return true;
+}
+
+bool Core::Setting::getPreferTags() {
+    return this->setting->value("prefer_tags", true).toBool();
+}
+
+void Core::Setting::setPreferTags(bool preferTags) {
+    this->setting->setValue("prefer_tags", preferTags);
+}
+
+int Core::Setting::getSortMode() {
+    return this->setting->value("sort_mode", 0).toInt();
+}
+
+void Core::Setting::setSortMode(int sortMode) {
+    this->setting->setValue("sort_mode", sortMode);
+}
+
+int Core::Setting::getCollectionFilter() {
+    return this->setting->value("collection_filter", -1).toInt();
+}
+
+void Core::Setting::setCollectionFilter(int collectionId) {
+    this->setting->setValue("collection_filter", collectionId);
+}
+
+QString Core::Setting::getSearchQuery() {
+    return this->setting->value("search_query", "").toString();
+}
+
+void Core::Setting::setSearchQuery(QString query) {
+    this->setting->setValue("search_query", query);
+}
+
+int Core::Setting::getReselectBehavior() {
+    return this->setting->value("reselect_behavior", ReselectTogglePlayPause).toInt();
+}
+
+void Core::Setting::setReselectBehavior(int reselectBehavior) {
+    this->setting->setValue("reselect_behavior", reselectBehavior);
+}
+
+int Core::Setting::getBookEndBehavior() {
+    return this->setting->value("on_book_end", BookEndNextInQueue).toInt();
+}
+
+void Core::Setting::setBookEndBehavior(int bookEndBehavior) {
+    this->setting->setValue("on_book_end", bookEndBehavior);
+}
+
+bool Core::Setting::getCombineDiscFolders() {
+    return this->setting->value("combine_disc_folders", false).toBool();
+}
+
+void Core::Setting::setCombineDiscFolders(bool combineDiscFolders) {
+    this->setting->setValue("combine_disc_folders", combineDiscFolders);
+
+    emit this->combineDiscFoldersUpdated(combineDiscFolders);
+}
+
+int Core::Setting::getFileSortMode() {
+    // before this setting the track numbers were used whenever tags were preferred
+    int defaultMode = this->getPreferTags() ? FileSortTrackTag : FileSortName;
+    return this->setting->value("file_sort", defaultMode).toInt();
+}
+
+void Core::Setting::setFileSortMode(int fileSortMode) {
+    if(fileSortMode == FileSortManual) {
+        return;
+    }
+
+    this->setting->setValue("file_sort", fileSortMode);
+}
+
+bool Core::Setting::getWatchDirectories() {
+    return this->setting->value("watch_directories", false).toBool();
+}
+
+void Core::Setting::setWatchDirectories(bool watchDirectories) {
+    this->setting->setValue("watch_directories", watchDirectories);
+
+    emit this->watchDirectoriesUpdated(watchDirectories);
+}
+
+bool Core::Setting::getScanOnStartup() {
+    return this->setting->value("auto_scan_on_startup", false).toBool();
+}
+
+void Core::Setting::setScanOnStartup(bool scanOnStartup) {
+    this->setting->setValue("auto_scan_on_startup", scanOnStartup);
+}
+
+bool Core::Setting::getNormalizeVolume() {
+    return this->setting->value("normalize_volume", false).toBool();
+}
+
+void Core::Setting::setNormalizeVolume(bool normalizeVolume) {
+    this->setting->setValue("normalize_volume", normalizeVolume);
+}
+
+bool Core::Setting::getShowTechnicalDetails() {
+    return this->setting->value("show_technical_details", false).toBool();
+}
+
+void Core::Setting::setShowTechnicalDetails(bool showTechnicalDetails) {
+    this->setting->setValue("show_technical_details", showTechnicalDetails);
+}
+
+bool Core::Setting::getChapterTitlesFromFileNames() {
+    return this->setting->value("chapter_titles_from_file_names", false).toBool();
+}
+
+void Core::Setting::setChapterTitlesFromFileNames(bool chapterTitles) {
+    this->setting->setValue("chapter_titles_from_file_names", chapterTitles);
+}
+
+int Core::Setting::getFileListColumns() {
+    return this->setting->value("file_list_columns", FileListDuration | FileListProgressPercent).toInt();
+}
+
+void Core::Setting::setFileListColumns(int columns) {
+    this->setting->setValue("file_list_columns", columns);
+}
+
+bool Core::Setting::getAutoPlayOnSelect() {
+    return this->setting->value("auto_play_on_select", false).toBool();
+}
+
+void Core::Setting::setAutoPlayOnSelect(bool autoPlayOnSelect) {
+    this->setting->setValue("auto_play_on_select", autoPlayOnSelect);
+}
+
+bool Core::Setting::getConfirmDestructive() {
+    return this->setting->value("confirm_destructive", true).toBool();
+}
+
+void Core::Setting::setConfirmDestructive(bool confirmDestructive) {
+    this->setting->setValue("confirm_destructive", confirmDestructive);
+}
+
+bool Core::Setting::getPauseOnOutputRemoved() {
+    return this->setting->value("pause_on_output_removed", false).toBool();
+}
+
+void Core::Setting::setPauseOnOutputRemoved(bool pauseOnOutputRemoved) {
+    this->setting->setValue("pause_on_output_removed", pauseOnOutputRemoved);
+
+    emit this->pauseOnOutputRemovedUpdated(pauseOnOutputRemoved);
+}
+
+bool Core::Setting::getShowRemainingTime() {
+    return this->setting->value("show_remaining_time", false).toBool();
+}
+
+void Core::Setting::setShowRemainingTime(bool showRemainingTime) {
+    this->setting->setValue("show_remaining_time", showRemainingTime);
+}
+
+int Core::Setting::getMinFileDurationSeconds() {
+    return this->setting->value("min_file_duration_secs", 0).toInt();
+}
+
+void Core::Setting::setMinFileDurationSeconds(int seconds) {
+    this->setting->setValue("min_file_duration_secs", seconds);
+}
+
+int Core::Setting::getFadeDuration() {
+    return this->setting->value("fade_duration_ms", 0).toInt();
+}
+
+void Core::Setting::setFadeDuration(int milliseconds) {
+    this->setting->setValue("fade_duration_ms", milliseconds);
+}
+
+int Core::Setting::getScanWorkers() {
+    auto workers = this->setting->value("scan_workers", QThread::idealThreadCount()).toInt();
+    return qBound(MIN_SCAN_WORKERS, workers, MAX_SCAN_WORKERS);
+}
+
+void Core::Setting::setScanWorkers(int workers) {
+    workers = qBound(MIN_SCAN_WORKERS, workers, MAX_SCAN_WORKERS);
+    this->setting->setValue("scan_workers", workers);
+
+    emit this->scanWorkersUpdated(workers);
+}
+
+int Core::Setting::getProgressSaveInterval() {
+    return this->setting->value("progress_save_interval_secs", 5).toInt();
+}
+
+void Core::Setting::setProgressSaveInterval(int seconds) {
+    this->setting->setValue("progress_save_interval_secs", seconds);
+}
+
+int Core::Setting::getTickInterval() {
+    auto milliseconds = this->setting->value("tick_interval_ms", MIN_TICK_INTERVAL_MS).toInt();
+    return qBound(MIN_TICK_INTERVAL_MS, milliseconds, MAX_TICK_INTERVAL_MS);
+}
+
+void Core::Setting::setTickInterval(int milliseconds) {
+    milliseconds = qBound(MIN_TICK_INTERVAL_MS, milliseconds, MAX_TICK_INTERVAL_MS);
+    this->setting->setValue("tick_interval_ms", milliseconds);
+
+    emit this->tickIntervalUpdated(milliseconds);
+}
+
+int Core::Setting::getSmartResumeSeconds() {
+    return this->setting->value("smart_resume_secs", 15).toInt();
+}
+
+void Core::Setting::setSmartResumeSeconds(int seconds) {
+    this->setting->setValue("smart_resume_secs", seconds);
+}
+
+int Core::Setting::getPauseRewindSeconds() {
+    return this->setting->value("pause_rewind_secs", 2).toInt();
+}
+
+void Core::Setting::setPauseRewindSeconds(int seconds) {
+    this->setting->setValue("pause_rewind_secs", seconds);
+}
+
+int Core::Setting::getCompleteToleranceSeconds() {
+    return this->setting->value("complete_tolerance_secs", 5).toInt();
+}
+
+void Core::Setting::setCompleteToleranceSeconds(int seconds) {
+    this->setting->setValue("complete_tolerance_secs", seconds);
+}
+
+int Core::Setting::getCompleteTolerancePercent() {
+    return this->setting->value("complete_tolerance_percent", 0).toInt();
+}
+
+void Core::Setting::setCompleteTolerancePercent(int percent) {
+    this->setting->setValue("complete_tolerance_percent", percent);
+}
+
+int Core::Setting::getListenedThresholdPercent() {
+    auto percent = this->setting->value("listened_threshold_percent", MAX_LISTENED_THRESHOLD_PERCENT).toInt();
+    return qBound(MIN_LISTENED_THRESHOLD_PERCENT, percent, MAX_LISTENED_THRESHOLD_PERCENT);
+}
+
+void Core::Setting::setListenedThresholdPercent(int percent) {
+    this->setting->setValue("listened_threshold_percent",
+                            qBound(MIN_LISTENED_THRESHOLD_PERCENT, percent, MAX_LISTENED_THRESHOLD_PERCENT));
+}
+
+QList<int> Core::Setting::getQueue() {
+    QList<int> queue;
+    for(auto &value : this->setting->value("queue").toList()) {
+        queue.append(value.toInt());
+    }
+
+    return queue;
+}
+
+void Core::Setting::setQueue(QList<int> queue) {
+    QVariantList values;
+    for(auto audiobookId : queue) {
+        values.append(audiobookId);
+    }
+    this->setting->setValue("queue", values);
+
+    emit this->queueUpdated(queue.size());
+}
+
+void Core::Setting::enqueueAudiobook(int audiobookId) {
+    auto queue = this->getQueue();
+    queue.append(audiobookId);
+    this->setQueue(queue);
+}
+
+int Core::Setting::takeNextQueuedAudiobook() {
+    auto queue = this->getQueue();
+    if(queue.isEmpty()) {
+        return -1;
+    }
+
+    auto audiobookId = queue.takeFirst();
+    this->setQueue(queue);
+
+    return audiobookId;
+}
+
+void Core::Setting::clearQueue() {
+    this->setQueue(QList<int>());
+}
+
 
 
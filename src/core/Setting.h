@@ -6,24 +6,219 @@
 #define NODOKANATIVE_SETTING_H
 
 #include <QSettings>
+#include <QList>
+#include <QStringList>
+
+const static int MIN_SCAN_WORKERS = 1;
+const static int MAX_SCAN_WORKERS = 16;
+const static int MIN_TICK_INTERVAL_MS = 250;
+const static int MAX_TICK_INTERVAL_MS = 2000;
+const static int MIN_LISTENED_THRESHOLD_PERCENT = 50;
+const static int MAX_LISTENED_THRESHOLD_PERCENT = 100;
+const static char* DEFAULT_ACCENT_COLOR = "#FEDB53";
+const static double MIN_ACCENT_CONTRAST = 4.5;
+const static double MIN_SPEED = 0.5;
+const static double MAX_SPEED = 2.5;
 
 namespace Core {
+    /**
+     * What double clicking the file that is already loaded does, the values are persisted in the settings
+     */
+    enum ReselectBehavior {
+        ReselectRestart = 0,
+        ReselectTogglePlayPause = 1,
+        ReselectIgnore = 2
+    };
+
+    /**
+     * What happens once the last file of an audiobook has finished, the values are persisted in the settings
+     */
+    enum BookEndBehavior {
+        BookEndStop = 0,
+        BookEndLoop = 1,
+        BookEndNextInQueue = 2,
+        BookEndNextUnfinished = 3
+    };
+
+    /**
+     * How the files of an audiobook are ordered, the values are persisted in the settings and the database
+     */
+    enum FileSortMode {
+        FileSortName = 0,
+        // falls back to the name when a file has no track number
+        FileSortTrackTag = 1,
+        FileSortModified = 2,
+        // only for a single audiobook, the order the user put the files in
+        FileSortManual = 3
+    };
+
+    /**
+     * What is shown below each file name in the file list, combined as flags
+     */
+    enum FileListColumn {
+        FileListDuration = 1,
+        FileListProgressPercent = 2,
+        FileListProgressBar = 4,
+        FileListCompleteMark = 8
+    };
+
     class Setting : public QObject {
     Q_OBJECT
 
         QSettings* setting;
 
+        void setQueue(QList<int> queue);
+
     public:
         Setting();
 
 
         int getVolume();
+
+        // volume for audiobooks that have never been played, 0 keeps the current volume
+        int getDefaultVolume();
+        void setDefaultVolume(int volume);
+
         int getCurrentAudiobookId();
         QString getSpeed();
         void setSpeed(QString speed);
 
+        // speeds offered in the speed chooser, the built-in list is used when none are set
+        QStringList getSpeedPresets();
+        // returns false and keeps the old presets if any of them is out of range
+        bool setSpeedPresets(QStringList presets);
+        // extensions always scanned, even if the file type isn't recognized as audio, e.g. "dsf"
+        QStringList getExtraFileExtensions();
+        // extensions never scanned, even if the file type is recognized as audio
+        QStringList getIgnoredFileExtensions();
+        // both return false and keep the old list unless every entry is a lowercase extension without the dot
+        bool setExtraFileExtensions(QStringList extensions);
+        bool setIgnoredFileExtensions(QStringList extensions);
+
+        // "#rrggbb" used for the top bar, the sliders and the highlighted menu entry
+        QString getAccentColor();
+        // an empty color goes back to the default, returns false and keeps the old color unless it is a hex color
+        // light enough for the black text drawn on it
+        bool setAccentColor(QString color);
+
+        bool getPreferTags();
+        void setPreferTags(bool preferTags);
+        int getSortMode();
+        void setSortMode(int sortMode);
+        // collection the audiobook list is limited to, -1 for the whole library
+        int getCollectionFilter();
+        void setCollectionFilter(int collectionId);
+        // last search in the audiobook list, empty if it was cleared
+        QString getSearchQuery();
+        void setSearchQuery(QString query);
+        int getReselectBehavior();
+        void setReselectBehavior(int reselectBehavior);
+        int getBookEndBehavior();
+        void setBookEndBehavior(int bookEndBehavior);
+
+        // treat "Disc 1", "CD 2", "Part 3" folders as one audiobook
+        bool getCombineDiscFolders();
+        void setCombineDiscFolders(bool combineDiscFolders);
+
+        // file order for audiobooks that don't have their own, manual isn't allowed here
+        int getFileSortMode();
+        void setFileSortMode(int fileSortMode);
+
+        // pick up audiobooks added to or removed from the scanning directories while running
+        bool getWatchDirectories();
+        void setWatchDirectories(bool watchDirectories);
+
+        // rescan the enabled scanning directories every time Nodoka starts
+        bool getScanOnStartup();
+        void setScanOnStartup(bool scanOnStartup);
+
+        // level the loudness between files, only read when the player starts
+        bool getNormalizeVolume();
+        void setNormalizeVolume(bool normalizeVolume);
+
+        // play the file an audiobook was left off at as soon as the user selects it
+        bool getAutoPlayOnSelect();
+        void setAutoPlayOnSelect(bool autoPlayOnSelect);
+
+        // ask before removing something that takes listening progress with it
+        bool getConfirmDestructive();
+        void setConfirmDestructive(bool confirmDestructive);
+
+        // pause when an audio output device goes away, e.g. headphones being unplugged
+        bool getPauseOnOutputRemoved();
+        void setPauseOnOutputRemoved(bool pauseOnOutputRemoved);
+
+        // count the time down to the end of the file instead of up from the start
+        bool getShowRemainingTime();
+        void setShowRemainingTime(bool showRemainingTime);
+
+        // show the codec, bitrate and sample rate in the file list
+        bool getShowTechnicalDetails();
+        void setShowTechnicalDetails(bool showTechnicalDetails);
+
+        // show each file as a chapter, titled after its file name without the track number and extension
+        bool getChapterTitlesFromFileNames();
+        void setChapterTitlesFromFileNames(bool chapterTitles);
+
+        // FileListColumn flags, the duration and progress percent are shown by default
+        int getFileListColumns();
+        void setFileListColumns(int columns);
+
+        // files shorter than this don't count toward the progress of an audiobook, 0 disables it
+        int getMinFileDurationSeconds();
+        void setMinFileDurationSeconds(int seconds);
+
+        // length of the fade when playback starts or pauses, 0 disables it
+        int getFadeDuration();
+        void setFadeDuration(int milliseconds);
+
+        // number of files checksummed at the same time, defaults to the number of cpus
+        int getScanWorkers();
+        void setScanWorkers(int workers);
+
+        // how often the position is saved while playing, pausing or switching files always saves it
+        int getProgressSaveInterval();
+        void setProgressSaveInterval(int seconds);
+
+        // how often the playback position is reported while playing, slower saves power
+        int getTickInterval();
+        void setTickInterval(int milliseconds);
+
+        // seconds to rewind when resuming after a break, 0 disables it
+        int getSmartResumeSeconds();
+        void setSmartResumeSeconds(int seconds);
+
+        // seconds to rewind when playback is resumed from a pause, 0 disables it
+        int getPauseRewindSeconds();
+        void setPauseRewindSeconds(int seconds);
+
+        // stopping this close to the end of a file counts as finishing it, 0 disables either check
+        int getCompleteToleranceSeconds();
+        void setCompleteToleranceSeconds(int seconds);
+        int getCompleteTolerancePercent();
+        void setCompleteTolerancePercent(int percent);
+
+        // percent from which a file is shown as listened, the stored progress is kept as it is
+        int getListenedThresholdPercent();
+        void setListenedThresholdPercent(int percent);
+
+        // audiobooks queued to play after the current one ends
+        QList<int> getQueue();
+        void enqueueAudiobook(int audiobookId);
+        int takeNextQueuedAudiobook();
+        void clearQueue();
+
     signals:
         void volumeUpdated(int volume);
+        void queueUpdated(int count);
+        void watchDirectoriesUpdated(bool watchDirectories);
+        void combineDiscFoldersUpdated(bool combineDiscFolders);
+        void speedPresetsUpdated();
+        void fileExtensionsUpdated();
+        void accentColorUpdated(QString color);
+        void scanWorkersUpdated(int workers);
+        void tickIntervalUpdated(int milliseconds);
+        void pauseOnOutputRemovedUpdated(bool pauseOnOutputRemoved);
 
     public slots:
         void setVolume(int volume);
@@ -3,20 +3,65 @@
 //
 
 #include "Util.h"
+#include "Setting.h"
 #include <QDir>
+#include <QFileInfo>
 #include <QCryptographicHash>
 #include <QDebug>
+#include <algorithm>
+#include <cmath>
+#include <vector>
+#include <QRegularExpression>
 
 static QString settingPath = "";
 
+// lets the database and the progress files live somewhere else, e.g. on an external drive
+const static char* DATA_DIR_ENV = "NODOKA_DATA_DIR";
+
+// any value turns on the timing lines, e.g. NODOKA_TIMING=1
+const static char* TIMING_ENV = "NODOKA_TIMING";
+
 QString Core::getSettingPath() {
     if(settingPath == "") {
-        settingPath = QStandardPaths::writableLocation(QStandardPaths::DataLocation);
+        auto customPath = QString::fromLocal8Bit(qgetenv(DATA_DIR_ENV)).trimmed();
+        if(!customPath.isEmpty()) {
+            settingPath = QDir(customPath).absolutePath();
+        } else {
+            settingPath = QStandardPaths::writableLocation(QStandardPaths::DataLocation);
+        }
     }
 
     return settingPath;
 }
 
+bool Core::isCustomSettingPath() {
+    return !QString::fromLocal8Bit(qgetenv(DATA_DIR_ENV)).trimmed().isEmpty();
+}
+
+bool Core::checkSettingPath(QString* errorMessage) {
+    auto path = getSettingPath();
+    createPathIfNotExists(path);
+
+    QFileInfo info(path);
+    QString message;
+    if(!info.isDir()) {
+        message = "The data directory " + path + " could not be created";
+    } else if(!info.isWritable()) {
+        message = "The data directory " + path + " is not writable";
+    } else {
+        return true;
+    }
+
+    if(qEnvironmentVariableIsSet(DATA_DIR_ENV)) {
+        message += " (set by " + QString(DATA_DIR_ENV) + ")";
+    }
+
+    if(errorMessage != nullptr) {
+        *errorMessage = message;
+    }
+    return false;
+}
+
 QString Core::getUniqueSettingPath(QString stringToHash) {
     QCryptographicHash hash(QCryptographicHash::Sha1);
     hash.addData(stringToHash.toLocal8Bit());
@@ -44,6 +89,301 @@ QString Core::convertTimeToString(long long timeToAdd) {
     return timeInFormat;
 }
 
+QString Core::formatTimestamp(QString audiobookName, QString fileName, long long time) {
+    auto totalSeconds = std::max(0LL, time) / 1000;
+    auto timestamp = QString("%1:%2:%3")
+            .arg(totalSeconds / 3600)
+            .arg((totalSeconds / 60) % 60, 2, 10, QChar('0'))
+            .arg(totalSeconds % 60, 2, 10, QChar('0'));
+
+    return audiobookName + " - " + fileName + " @ " + timestamp;
+}
+
+bool Core::isNearEnd(long long currentTime, long long duration, int toleranceSeconds, int tolerancePercent) {
+    if(duration <= 0 || currentTime < 0) {
+        return false;
+    }
+
+    auto remaining = std::max(0LL, duration - currentTime);
+    if(toleranceSeconds > 0 && remaining <= toleranceSeconds * 1000LL) {
+        return true;
+    }
+
+    return tolerancePercent > 0 && remaining * 100 <= duration * tolerancePercent;
+}
+
+long long Core::remainingPlaybackTime(long long duration, long long currentTime, double speed) {
+    if(speed <= 0) {
+        speed = 1.0;
+    }
+
+    auto remaining = std::max(0LL, duration - currentTime);
+    return static_cast<long long>(remaining / speed);
+}
+
+bool Core::isTimingEnabled() {
+    static bool timingEnabled = !qgetenv(TIMING_ENV).isEmpty();
+    return timingEnabled;
+}
+
+void Core::logTiming(QString operation, QString path, qint64 bytes, qint64 elapsedMs) {
+    if(!isTimingEnabled()) {
+        return;
+    }
+
+    qInfo().noquote() << "timing:" << operation
+                      << "path=" + path
+                      << "bytes=" + QString::number(bytes)
+                      << "elapsed_ms=" + QString::number(elapsedMs);
+}
+
+QString Core::stepSpeed(QString currentSpeed, const QStringList& presets, bool faster, bool fine) {
+    auto speed = currentSpeed.toDouble();
+    auto nextSpeed = speed;
+
+    if(fine) {
+        // count in whole steps, adding 0.05 over and over would drift away from the values shown
+        auto steps = std::round(speed * 20);
+        nextSpeed = (steps + (faster ? 1 : -1)) / 20.0;
+    } else {
+        std::vector<double> presetSpeeds;
+        for(auto &preset : presets) {
+            presetSpeeds.push_back(preset.toDouble());
+        }
+        std::sort(presetSpeeds.begin(), presetSpeeds.end());
+
+        // the speed may sit between two presets after fine steps
+        if(faster) {
+            auto next = std::find_if(presetSpeeds.begin(), presetSpeeds.end(), [speed](double presetSpeed) {
+                return presetSpeed > speed + 0.001;
+            });
+            if(next != presetSpeeds.end()) {
+                nextSpeed = *next;
+            }
+        } else {
+            auto previous = std::find_if(presetSpeeds.rbegin(), presetSpeeds.rend(), [speed](double presetSpeed) {
+                return presetSpeed < speed - 0.001;
+            });
+            if(previous != presetSpeeds.rend()) {
+                nextSpeed = *previous;
+            }
+        }
+    }
+
+    return QString::number(qBound(MIN_SPEED, nextSpeed, MAX_SPEED));
+}
+
+int Core::effectiveTickInterval(int tickInterval, bool isSeeking) {
+    if(isSeeking) {
+        return 0;
+    }
+
+    return qBound(MIN_TICK_INTERVAL_MS, tickInterval, MAX_TICK_INTERVAL_MS);
+}
+
+long long Core::parseTimeString(const QString& text, bool* ok) {
+    *ok = false;
+
+    auto parts = text.trimmed().split(':');
+    if(parts.size() > 3) {
+        return 0;
+    }
+
+    long long totalSeconds = 0;
+    for(int i = 0; i < parts.size(); i++) {
+        bool isNumber = false;
+        auto value = parts[i].toLongLong(&isNumber);
+        if(!isNumber || value < 0) {
+            return 0;
+        }
+
+        // only the first field can go past 59
+        if(i > 0 && value >= 60) {
+            return 0;
+        }
+
+        totalSeconds = totalSeconds * 60 + value;
+    }
+
+    *ok = true;
+    return totalSeconds * 1000;
+}
+
+int Core::naturalCompare(const QString& first, const QString& second) {
+    int i = 0;
+    int j = 0;
+
+    while(i < first.size() && j < second.size()) {
+        if(first[i].isDigit() && second[j].isDigit()) {
+            // skip the leading zeros, they don't change the numeric value
+            int startFirst = i;
+            while(startFirst < first.size() && first[startFirst].isDigit() && first[startFirst].digitValue() == 0) {
+                startFirst++;
+            }
+            int startSecond = j;
+            while(startSecond < second.size() && second[startSecond].isDigit() && second[startSecond].digitValue() == 0) {
+                startSecond++;
+            }
+
+            int endFirst = startFirst;
+            while(endFirst < first.size() && first[endFirst].isDigit()) {
+                endFirst++;
+            }
+            int endSecond = startSecond;
+            while(endSecond < second.size() && second[endSecond].isDigit()) {
+                endSecond++;
+            }
+
+            // a number with more significant digits is always larger
+            int lengthFirst = endFirst - startFirst;
+            int lengthSecond = endSecond - startSecond;
+            if(lengthFirst != lengthSecond) {
+                return lengthFirst < lengthSecond ? -1 : 1;
+            }
+
+            for(int k = 0; k < lengthFirst; k++) {
+                int digitFirst = first[startFirst + k].digitValue();
+                int digitSecond = second[startSecond + k].digitValue();
+                if(digitFirst != digitSecond) {
+                    return digitFirst < digitSecond ? -1 : 1;
+                }
+            }
+
+            // same value, the one with fewer leading zeros goes first
+            int zerosFirst = startFirst - i;
+            int zerosSecond = startSecond - j;
+            if(zerosFirst != zerosSecond) {
+                return zerosFirst < zerosSecond ? -1 : 1;
+            }
+
+            i = endFirst;
+            j = endSecond;
+        } else {
+            auto charFirst = first[i].toCaseFolded();
+            auto charSecond = second[j].toCaseFolded();
+            if(charFirst != charSecond) {
+                return charFirst < charSecond ? -1 : 1;
+            }
+
+            i++;
+            j++;
+        }
+    }
+
+    int remainingFirst = first.size() - i;
+    int remainingSecond = second.size() - j;
+    if(remainingFirst != remainingSecond) {
+        return remainingFirst < remainingSecond ? -1 : 1;
+    }
+
+    // only the case differs, keep the order stable
+    return QString::compare(first, second);
+}
+
+bool Core::naturalLessThan(const QString& first, const QString& second) {
+    return naturalCompare(first, second) < 0;
+}
+
+bool Core::modifiedEarlier(const QString& firstPath, const QString& secondPath) {
+    return QFileInfo(firstPath).lastModified() < QFileInfo(secondPath).lastModified();
+}
+
+int Core::compareParentDirectory(const QString& firstPath, const QString& secondPath) {
+    return naturalCompare(QFileInfo(firstPath).path(), QFileInfo(secondPath).path());
+}
+
+QMap<QString, QStringList> Core::groupDirectoriesByParent(const QStringList& paths) {
+    QMap<QString, QStringList> groups;
+    for(auto &path : paths) {
+        auto parent = QFileInfo(QDir::cleanPath(path)).path();
+        groups[parent] << path;
+    }
+
+    for(auto &group : groups) {
+        std::sort(group.begin(), group.end(), Core::naturalLessThan);
+    }
+
+    return groups;
+}
+
+bool Core::hasNumberingGaps(const QStringList& fileNames) {
+    static const QRegularExpression numberPattern("\\d+");
+
+    std::vector<long long> numbers;
+    for(auto &fileName : fileNames) {
+        auto match = numberPattern.match(fileName);
+        if(!match.hasMatch()) {
+            return false;
+        }
+
+        numbers.push_back(match.captured(0).toLongLong());
+    }
+
+    // multi-disc audiobooks restart the numbering for each disc, repeated numbers are fine
+    std::sort(numbers.begin(), numbers.end());
+    numbers.erase(std::unique(numbers.begin(), numbers.end()), numbers.end());
+
+    for(size_t i = 1; i < numbers.size(); i++) {
+        if(numbers[i] - numbers[i - 1] > 1) {
+            return true;
+        }
+    }
+
+    return false;
+}
+
+QString Core::chapterTitleFromFileName(const QString& fileName) {
+    static const QRegularExpression extensionPattern("\\.[A-Za-z0-9]{1,5}$");
+    static const QRegularExpression trackNumberPattern("^\\d+\\s*[-.)\\]:]*\\s*");
+
+    auto title = fileName;
+    title.remove(extensionPattern);
+    title.replace("_", " ");
+    title = title.trimmed();
+
+    // a name that is nothing but a number, like "1984", is kept as it is
+    auto withoutNumber = title;
+    withoutNumber.remove(trackNumberPattern);
+    if(!withoutNumber.isEmpty()) {
+        title = withoutNumber;
+    }
+
+    return title;
+}
+
+QColor Core::parseHexColor(const QString& text, bool* ok) {
+    static const QRegularExpression hexPattern("^#?([0-9a-fA-F]{3}|[0-9a-fA-F]{6})$");
+
+    auto match = hexPattern.match(text.trimmed());
+    *ok = match.hasMatch();
+    if(!*ok) {
+        return QColor();
+    }
+
+    auto digits = match.captured(1);
+    if(digits.size() == 3) {
+        // each digit stands for itself twice, "fd5" is "ffdd55"
+        digits = QString(digits[0]) + digits[0] + digits[1] + digits[1] + digits[2] + digits[2];
+    }
+
+    return QColor("#" + digits);
+}
+
+static double relativeLuminance(const QColor& color) {
+    auto channel = [](double value) {
+        return value <= 0.03928 ? value / 12.92 : std::pow((value + 0.055) / 1.055, 2.4);
+    };
+
+    return 0.2126 * channel(color.redF()) + 0.7152 * channel(color.greenF()) + 0.0722 * channel(color.blueF());
+}
+
+double Core::contrastRatio(const QColor& first, const QColor& second) {
+    auto firstLuminance = relativeLuminance(first);
+    auto secondLuminance = relativeLuminance(second);
+
+    return (std::max(firstLuminance, secondLuminance) + 0.05) / (std::min(firstLuminance, secondLuminance) + 0.05);
+}
+
 void Core::createPathIfNotExists(QString path) {
     QDir dir(path);
 
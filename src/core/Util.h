@@ -2,16 +2,78 @@
 
 #include <QTime>
 #include <QString>
+#include <QStringList>
+#include <QMap>
 #include <QStandardPaths>
+#include <QColor>
 
 namespace Core {
 
     void createPathIfNotExists(QString path);
     void createSettingPathIfNotExists();
 
+    // NODOKA_DATA_DIR when it is set, the standard data location otherwise
     QString getSettingPath();
+    bool isCustomSettingPath();
+
+    // create the data directory and make sure it can be written to, errorMessage explains why not
+    bool checkSettingPath(QString* errorMessage = nullptr);
+
     QString getUniqueSettingPath(QString stringToHash);
 
     QString convertTimeToString(long long time);
 
+    // "Audiobook - File @ 1:02:03", for sharing where someone is in a book
+    QString formatTimestamp(QString audiobookName, QString fileName, long long time);
+
+    // true if currentTime is within the given seconds or percent of the end, a tolerance of 0 is ignored
+    bool isNearEnd(long long currentTime, long long duration, int toleranceSeconds, int tolerancePercent);
+
+    // wall clock time left in a file at the given playback speed
+    long long remainingPlaybackTime(long long duration, long long currentTime, double speed);
+
+    // true when NODOKA_TIMING is set, then scans, checksums and file loads log how long they took
+    bool isTimingEnabled();
+    // a "timing:" line with the operation, the file it worked on, its size and the elapsed time
+    void logTiming(QString operation, QString path, qint64 bytes, qint64 elapsedMs);
+
+    // the next speed up or down from currentSpeed, either the neighbouring preset or a fine step of 0.05,
+    // the result always stays between MIN_SPEED and MAX_SPEED
+    QString stepSpeed(QString currentSpeed, const QStringList& presets, bool faster, bool fine);
+
+    // how long to wait between position updates, every update is passed on while the user is seeking
+    int effectiveTickInterval(int tickInterval, bool isSeeking);
+
+    // parse "h:mm:ss", "mm:ss" or plain seconds into milliseconds, ok is set to false on invalid input
+    long long parseTimeString(const QString& text, bool* ok);
+
+    // compare two strings in natural order, digit runs are compared by their numeric value
+    // so "Track2" comes before "Track10", returns <0, 0 or >0 like QString::compare
+    int naturalCompare(const QString& first, const QString& second);
+    bool naturalLessThan(const QString& first, const QString& second);
+
+    // true if the file at firstPath was modified before the one at secondPath, for sorting files by date
+    bool modifiedEarlier(const QString& firstPath, const QString& secondPath);
+
+    // compare the folders two files are in, in natural order, so the files of a combined audiobook
+    // stay grouped by disc whatever they are sorted by within the disc
+    int compareParentDirectory(const QString& firstPath, const QString& secondPath);
+
+    // group directories by the folder they are in, the paths in each group are in natural order
+    QMap<QString, QStringList> groupDirectoriesByParent(const QStringList& paths);
+
+    // true if the first number in each file name skips a value, e.g. "Chapter 06" followed by "Chapter 08"
+    // names without a number mean the files aren't numbered, so that never counts as a gap
+    bool hasNumberingGaps(const QStringList& fileNames);
+
+    // a chapter title for a file of a book split into one file per chapter, the extension and a leading
+    // track number are left out, e.g. "01 - The Beginning.mp3" becomes "The Beginning"
+    QString chapterTitleFromFileName(const QString& fileName);
+
+    // parse "#rrggbb" or "#rgb", the "#" is optional, ok is set to false on invalid input
+    QColor parseHexColor(const QString& text, bool* ok);
+
+    // WCAG contrast ratio between two colors, from 1 for the same luminance to 21 for black on white
+    double contrastRatio(const QColor& first, const QColor& second);
+
 }
\ No newline at end of file
@@ -3,11 +3,15 @@
 //
 
 #include <src/core/AudiobookScan.h>
+#include <QMetaObject>
 #include "AddRemoveVisitor.h"
 
-AddRemoveVisitor::AddRemoveVisitor(Audiobook *audiobookModel, QDir baseDirectory) {
+AddRemoveVisitor::AddRemoveVisitor(Audiobook *audiobookModel, QSqlRecord directoryRecord, bool combineDiscFolders) {
     this->audiobookModel = audiobookModel;
-    this->baseDirectory = baseDirectory;
+    this->directoryRecord = directoryRecord;
+    this->combineDiscFolders = combineDiscFolders;
+    this->scannedFileCount = 0;
+    this->scannedBytes = 0;
 }
 
 void AddRemoveVisitor::accept(const std::shared_ptr<QDir>& directory) {
@@ -38,6 +42,10 @@ void AddRemoveVisitor::accept(const std::shared_ptr<QDir>& directory) {
         } else if(potentialFile->exists()) {
             if(Core::isAudiobookFile(potentialFile, currentPath)) {
                 loadedAudioFiles.push_back(potentialFile);
+
+                // the iterator already has the size, so this doesn't touch the disk again
+                this->scannedFileCount++;
+                this->scannedBytes += it.fileInfo().size();
             }
         }
     }
@@ -46,7 +54,7 @@ void AddRemoveVisitor::accept(const std::shared_ptr<QDir>& directory) {
     if(loadedDirectories.size() > 0) {
         // if all of the directories are similar, then simply make a call
         // to register this audiobook right away, if not already registered
-        if(Core::checkDirectorysimilarity(loadedDirectories)) {
+        if(Core::checkDirectorysimilarity(loadedDirectories, this->combineDiscFolders)) {
             this->addRemoveAudiobook(directory, loadedDirectories, loadedAudioFiles);
         } else {
             for(auto &dir : loadedDirectories) {
@@ -74,7 +82,19 @@ void AddRemoveVisitor::accept(const QString directory) {
     }
 }
 
+int AddRemoveVisitor::getScannedFileCount() {
+    return this->scannedFileCount;
+}
+
+qint64 AddRemoveVisitor::getScannedBytes() {
+    return this->scannedBytes;
+}
+
 void AddRemoveVisitor::addRemoveAudiobook(const std::shared_ptr<QDir>& directory,
                                           const std::vector<std::shared_ptr<QDir>>& subdirectories,
                                           const std::vector<std::shared_ptr<QFile>>& files) {
+    // the scan runs on a worker thread, the model and its database connection are only used from the ui thread
+    QMetaObject::invokeMethod(this->audiobookModel, "registerAudiobookIfNew", Qt::QueuedConnection,
+                              Q_ARG(QSqlRecord, this->directoryRecord),
+                              Q_ARG(QString, directory->path()));
 }
@@ -10,6 +10,7 @@
 #include "AbstractElementScanner.h"
 #include <QDir>
 #include <QFile>
+#include <QSqlRecord>
 
 /**
  * AddRemoveVisitor is called whenever a directory is scanned, or re-scanned
@@ -17,17 +18,26 @@
 class AddRemoveVisitor : public AbstractElementScanner {
 private:
     Audiobook* audiobookModel;
+    bool combineDiscFolders;
 
-    QDir baseDirectory;
+    QSqlRecord directoryRecord;
+
+    // audio files the scan went through, for the timing summary
+    int scannedFileCount;
+    qint64 scannedBytes;
 
 public:
     AddRemoveVisitor(Audiobook* audiobookModel,
-                     QDir baseDirectory);
+                     QSqlRecord directoryRecord,
+                     bool combineDiscFolders = false);
 
     void accept(const std::shared_ptr<QDir>& dir);
     void accept(const std::shared_ptr<QFile>& file);
     void accept(const QString directory);
 
+    int getScannedFileCount();
+    qint64 getScannedBytes();
+
     void addRemoveAudiobook(const std::shared_ptr<QDir>& directory,
                             const std::vector<std::shared_ptr<QDir>>& subdirectories,
                             const std::vector<std::shared_ptr<QFile>>& files);
@@ -12,7 +12,9 @@ Core::PlayerScanTask::PlayerScanTask(Core::ScanPlayer *player, std::shared_ptr<A
 
 void Core::PlayerScanTask::run() {
     // if every file has already been scanned, skip this step
-    if(audiobook->hasDuration() && audiobook->allFileDurationScanned()) {
+    if(audiobook->hasDuration() && audiobook->allFileDurationScanned() && audiobook->allFileMetadataScanned()) {
+        // libraries scanned before covers were supported still need one
+        audiobook->updateCover();
         return;
     }
 
@@ -0,0 +1,25 @@
+#include "ScanDirectoryTask.h"
+#include "RescanAllTask.h"
+
+Core::RescanAllTask::RescanAllTask(QList<QSqlRecord> directoryRecords,
+                                   Audiobook *audiobook,
+                                   Setting *setting,
+                                   std::function<void (QString)> progressFunction) {
+    this->directoryRecords = directoryRecords;
+    this->audiobook = audiobook;
+    this->setting = setting;
+    this->progressFunction = progressFunction;
+}
+
+void Core::RescanAllTask::run() {
+    for(auto &record : this->directoryRecords) {
+        this->progressFunction(record.value("full_path").toString());
+
+        // runs on this thread, the audiobooks it finds are registered on the ui thread
+        ScanDirectoryTask task(record, this->audiobook, this->setting);
+        task.run();
+    }
+
+    // queued behind the registrations, so the list is complete by the time this arrives on the ui thread
+    this->progressFunction(QString());
+}
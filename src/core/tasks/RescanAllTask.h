@@ -0,0 +1,30 @@
+#ifndef NODOKANATIVE_RESCANALLTASK_H
+#define NODOKANATIVE_RESCANALLTASK_H
+
+#include <QRunnable>
+#include <QSqlRecord>
+#include <functional>
+#include <src/model/Audiobook.h>
+#include <src/core/Setting.h>
+
+namespace Core {
+    // rescan several scanning directories one after the other
+    class RescanAllTask : public QRunnable {
+        QList<QSqlRecord> directoryRecords;
+        Audiobook* audiobook;
+        Setting* setting;
+        std::function<void (QString)> progressFunction;
+
+    public:
+        // progressFunction is called from the scanning thread with the directory being scanned,
+        // and with an empty string once every directory is done
+        RescanAllTask(QList<QSqlRecord> directoryRecords,
+                      Audiobook* audiobook,
+                      Setting* setting,
+                      std::function<void (QString)> progressFunction);
+        void run();
+    };
+}
+
+
+#endif //NODOKANATIVE_RESCANALLTASK_H
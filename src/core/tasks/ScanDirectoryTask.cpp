@@ -4,19 +4,45 @@
 
 #include <src/core/AudiobookScan.h>
 #include <src/core/directory-element-scan/AddRemoveVisitor.h>
+#include <src/core/Util.h>
+#include <QElapsedTimer>
 #include "ScanDirectoryTask.h"
 
 void Core::ScanDirectoryTask::run() {
     QString path = this->record.value("full_path").toString();
-    AddRemoveVisitor scanner(this->audiobook, path);
+    AddRemoveVisitor scanner(this->audiobook, this->record, this->setting->getCombineDiscFolders());
 
     qDebug() << "scanner accept called";
 
+    if(!this->startPath.isEmpty()) {
+        path = this->startPath;
+    }
+
+    QElapsedTimer timer;
+    timer.start();
+
     // start scanning from the base directory path
     scanner.accept(path);
+
+    if(isTimingEnabled()) {
+        logScanSummary(path, scanner.getScannedFileCount(), scanner.getScannedBytes(), timer.elapsed());
+    }
+}
+
+void Core::ScanDirectoryTask::logScanSummary(QString path, int fileCount, qint64 totalBytes, qint64 elapsedMs) {
+    qInfo().noquote() << "timing: scan_directory"
+                      << "path=" + path
+                      << "files=" + QString::number(fileCount)
+                      << "bytes=" + QString::number(totalBytes)
+                      << "elapsed_ms=" + QString::number(elapsedMs);
 }
 
-Core::ScanDirectoryTask::ScanDirectoryTask(QSqlRecord directoryRecord, Audiobook *audiobook) {
+Core::ScanDirectoryTask::ScanDirectoryTask(QSqlRecord directoryRecord,
+                                           Audiobook *audiobook,
+                                           Setting *setting,
+                                           QString startPath) {
     this->record = directoryRecord;
     this->audiobook = audiobook;
+    this->setting = setting;
+    this->startPath = startPath;
 }
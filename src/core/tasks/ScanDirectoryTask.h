@@ -7,14 +7,24 @@
 
 #include <QSqlRecord>
 #include <src/model/Audiobook.h>
+#include <src/core/Setting.h>
 
 namespace Core {
     class ScanDirectoryTask : public QRunnable {
         QSqlRecord record;
         Audiobook* audiobook;
+        Setting* setting;
+        QString startPath;
+
+        // log how many audio files and bytes the scan went through, for NODOKA_TIMING
+        void logScanSummary(QString path, int fileCount, qint64 totalBytes, qint64 elapsedMs);
 
     public:
-        ScanDirectoryTask(QSqlRecord directoryRecord, Audiobook* audiobook);
+        // startPath limits the scan to a subdirectory of the scanning directory
+        ScanDirectoryTask(QSqlRecord directoryRecord,
+                          Audiobook* audiobook,
+                          Setting* setting,
+                          QString startPath = QString());
         void run();
     };
 }
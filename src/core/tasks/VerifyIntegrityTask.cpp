@@ -0,0 +1,31 @@
+#include "VerifyIntegrityTask.h"
+
+Core::VerifyIntegrityTask::VerifyIntegrityTask(std::vector<std::shared_ptr<AudiobookFileProxy>> files,
+                                               std::function<void (int, int)> progressFunction,
+                                               std::function<void (QStringList)> finishedFunction) {
+    this->files = files;
+    this->progressFunction = progressFunction;
+    this->finishedFunction = finishedFunction;
+}
+
+void Core::VerifyIntegrityTask::run() {
+    QStringList problems;
+    int total = static_cast<int>(this->files.size());
+    int checked = 0;
+
+    this->progressFunction(checked, total);
+
+    for(auto &file : this->files) {
+        auto integrity = file->verifyCheckSum();
+        if(integrity == IntegrityChanged) {
+            problems << file->name() + " has changed or is damaged";
+        } else if(integrity == IntegrityMissing) {
+            problems << file->name() + " is missing";
+        }
+
+        checked++;
+        this->progressFunction(checked, total);
+    }
+
+    this->finishedFunction(problems);
+}
@@ -0,0 +1,29 @@
+#ifndef NODOKANATIVE_VERIFYINTEGRITYTASK_H
+#define NODOKANATIVE_VERIFYINTEGRITYTASK_H
+
+#include <QRunnable>
+#include <QStringList>
+#include <functional>
+#include <memory>
+#include <vector>
+#include <src/proxy-objects/AudiobookFileProxy.h>
+
+namespace Core {
+    // check the files of an audiobook against the checksums saved when they were first scanned
+    class VerifyIntegrityTask : public QRunnable {
+        std::vector<std::shared_ptr<AudiobookFileProxy>> files;
+        std::function<void (int, int)> progressFunction;
+        std::function<void (QStringList)> finishedFunction;
+
+    public:
+        // both functions are called from the checking thread, progressFunction with the number of files
+        // checked so far and the total, finishedFunction with a description of every file that failed
+        VerifyIntegrityTask(std::vector<std::shared_ptr<AudiobookFileProxy>> files,
+                            std::function<void (int, int)> progressFunction,
+                            std::function<void (QStringList)> finishedFunction);
+        void run();
+    };
+}
+
+
+#endif //NODOKANATIVE_VERIFYINTEGRITYTASK_H
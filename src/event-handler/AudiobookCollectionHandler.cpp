@@ -2,6 +2,7 @@
 // Created by mistlight on 2017/02/04.
 //
 
+#include <src/core/DuplicateScan.h>
 #include "AudiobookCollectionHandler.h"
 
 AudiobookCollectionHandler::AudiobookCollectionHandler(Audiobook *audiobookModel,
@@ -14,6 +15,8 @@ void AudiobookCollectionHandler::directoryAdded(QString path) {
     std::shared_ptr<QDir> directoryObject(new QDir(path));
 
     if(directoryObject->isReadable()) {
+        // adding the folder by hand brings back a merged duplicate
+        Core::clearMergedDuplicate(directoryObject->path());
         this->audiobookModel->registerAudiobook(QSqlRecord(), directoryObject);
     }
 }
@@ -3,26 +3,41 @@
 //
 
 #include <QDebug>
+#include <QMessageBox>
+#include <QActionGroup>
+#include <QThreadPool>
+#include <QDir>
+#include <QFileDialog>
+#include <QInputDialog>
 #include <QtSql/QSqlTableModel>
+#include <QtSql/QSqlError>
 #include <src/model/Audiobook.h>
 #include <src/model/FileDisplayModel.h>
+#include <src/core/tasks/VerifyIntegrityTask.h>
 #include "AudiobookListViewHandler.h"
 
 AudiobookListViewHandler::AudiobookListViewHandler(QMainWindow *window,
                                                    QListView *audiobookListView,
                                                    QListView *fileListView,
                                                    std::shared_ptr<ProxyManager> proxyManager,
+                                                   Core::Setting* setting,
                                                    QObject *parent
 ): QObject(parent) {
     this->mainWindow = window;
     this->audiobookListView = audiobookListView;
     this->fileListView = fileListView;
     this->proxyManager = proxyManager;
+    this->setting = setting;
+
+    // the result comes in from the checking thread, so it is shown once it is back on the ui thread
+    connect(this, &AudiobookListViewHandler::integrityChecked,
+            this, &AudiobookListViewHandler::showIntegrityResult);
 }
 
 
 void AudiobookListViewHandler::handleResetAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
     audiobook->resetReadStatus();
+    this->refreshViews();
 }
 
 void AudiobookListViewHandler::handleDeleteAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
@@ -60,10 +75,90 @@ void AudiobookListViewHandler::contextMenuRequested(const QPoint &position) {
             this->handleRescan(audiobookProxy);
         });
 
+        auto removeMissingFilesAction = new QAction("Remove Missing Files");
+        connect(removeMissingFilesAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleRemoveMissingFiles(audiobookProxy);
+        });
+
+        auto renameAction = new QAction("Rename...");
+        connect(renameAction, &QAction::triggered, [this, audiobookProxy, record] () {
+            this->handleRename(audiobookProxy, record);
+        });
+
+        auto mergeAction = new QAction("Merge Into...");
+        connect(mergeAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleMergeInto(audiobookProxy);
+        });
+
+        auto setCoverAction = new QAction("Set Cover...");
+        connect(setCoverAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleSetCover(audiobookProxy);
+        });
+
+        auto resetCoverAction = new QAction("Reset Cover");
+        resetCoverAction->setEnabled(!audiobookProxy->getCoverOverride().isEmpty());
+        connect(resetCoverAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleResetCover(audiobookProxy);
+        });
+
+        auto verifyAction = new QAction("Verify Files");
+        connect(verifyAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleVerifyIntegrity(audiobookProxy);
+        });
+
+        auto addToQueueAction = new QAction("Add to Queue");
+        connect(addToQueueAction, &QAction::triggered, [this, audiobookProxy] () {
+            this->handleAddToQueue(audiobookProxy);
+        });
+
         QMenu *menu = new QMenu(this->mainWindow);
+
+        auto resumeMenu = new QMenu("Resume From", menu);
+        auto resumeModeGroup = new QActionGroup(resumeMenu);
+        auto currentResumeMode = audiobookProxy->getResumeMode();
+        auto addResumeModeAction = [this, audiobookProxy, resumeMenu, resumeModeGroup, currentResumeMode]
+                (QString name, AudiobookResumeMode resumeMode) {
+            auto action = resumeMenu->addAction(name);
+            action->setCheckable(true);
+            action->setChecked(resumeMode == currentResumeMode);
+            resumeModeGroup->addAction(action);
+            connect(action, &QAction::triggered, [this, audiobookProxy, resumeMode] () {
+                this->handleSetResumeMode(audiobookProxy, resumeMode);
+            });
+        };
+        addResumeModeAction("Exact Position", ResumeExactPosition);
+        addResumeModeAction("Start of File", ResumeStartOfFile);
+
+        auto fileSortMenu = new QMenu("Sort Files By", menu);
+        auto fileSortGroup = new QActionGroup(fileSortMenu);
+        auto currentFileSortMode = audiobookProxy->getFileSortMode();
+        auto addFileSortAction = [this, audiobookProxy, fileSortMenu, fileSortGroup, currentFileSortMode]
+                (QString name, Core::FileSortMode fileSortMode) {
+            auto action = fileSortMenu->addAction(name);
+            action->setCheckable(true);
+            action->setChecked(fileSortMode == currentFileSortMode);
+            fileSortGroup->addAction(action);
+            connect(action, &QAction::triggered, [this, audiobookProxy, fileSortMode] () {
+                this->handleSetFileSortMode(audiobookProxy, fileSortMode);
+            });
+        };
+        addFileSortAction("Name", Core::FileSortName);
+        addFileSortAction("Track Number", Core::FileSortTrackTag);
+        addFileSortAction("Date Modified", Core::FileSortModified);
+        addFileSortAction("Manual", Core::FileSortManual);
+
+        menu->addAction(addToQueueAction);
         menu->addAction(resetAction);
         menu->addAction(markAsReadAction);
         menu->addAction(rescanAction);
+        menu->addAction(removeMissingFilesAction);
+        menu->addAction(verifyAction);
+        menu->addMenu(resumeMenu);
+        menu->addMenu(fileSortMenu);
+        menu->addAction(renameAction);
+        menu->addAction(mergeAction);
+        menu->addAction(setCoverAction);
+        menu->addAction(resetCoverAction);
         menu->addAction(removeAction);
         menu->exec(this->audiobookListView->mapToGlobal(position));
 
@@ -76,11 +171,166 @@ void AudiobookListViewHandler::contextMenuRequested(const QPoint &position) {
 
 void AudiobookListViewHandler::handleMarkAsReadAudiobook(std::shared_ptr<AudiobookProxy> audiobook) {
     audiobook->markAsRead();
+    this->refreshViews();
 }
 
 void AudiobookListViewHandler::handleRescan(std::shared_ptr<AudiobookProxy> audiobook) {
     audiobook->rescan();
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleAddToQueue(std::shared_ptr<AudiobookProxy> audiobook) {
+    this->setting->enqueueAudiobook(audiobook->getId());
+}
+
+void AudiobookListViewHandler::handleSetFileSortMode(std::shared_ptr<AudiobookProxy> audiobook,
+                                                     Core::FileSortMode fileSortMode) {
+    audiobook->setFileSortMode(fileSortMode);
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleSetResumeMode(std::shared_ptr<AudiobookProxy> audiobook,
+                                                   AudiobookResumeMode resumeMode) {
+    audiobook->setResumeMode(resumeMode);
+}
+
+void AudiobookListViewHandler::handleRename(std::shared_ptr<AudiobookProxy> audiobook, QSqlRecord record) {
+    // start from the name that is shown right now
+    auto currentName = audiobook->getDisplayName();
+    if(currentName.isEmpty()) {
+        currentName = record.value("name").toString();
+        if(this->setting->getPreferTags() && !record.value("title").isNull()) {
+            currentName = record.value("title").toString();
+        }
+    }
+
+    bool ok = false;
+    auto displayName = QInputDialog::getText(this->mainWindow, "Rename Audiobook",
+                                             "Name (leave empty to use the folder name):",
+                                             QLineEdit::Normal, currentName, &ok);
+    if(!ok) {
+        return;
+    }
+
+    // the folder is left alone, so a rescan keeps the name
+    audiobook->setDisplayName(displayName);
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleMergeInto(std::shared_ptr<AudiobookProxy> audiobook) {
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE id != ? ORDER BY COALESCE(display_name, name) COLLATE NOCASE ASC, id ASC");
+    query.addBindValue(audiobook->getId());
+    if(!query.exec()) {
+        qWarning() << "audiobook list query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    QStringList names;
+    std::vector<QSqlRecord> records;
+    while(query.next()) {
+        auto record = query.record();
+        auto name = record.value("display_name").isNull() ?
+                    record.value("name").toString() : record.value("display_name").toString();
+        // the folder tells apart audiobooks with the same name
+        names << name + " (" + QDir::toNativeSeparators(record.value("full_path").toString()) + ")";
+        records.push_back(record);
+    }
+
+    if(records.empty()) {
+        QMessageBox::information(this->mainWindow, "Merge Audiobooks", "There is no other audiobook to merge into.");
+        return;
+    }
+
+    bool ok = false;
+    auto chosenName = QInputDialog::getItem(this->mainWindow, "Merge Audiobooks",
+                                            "Add the files of this audiobook to the end of:",
+                                            names, 0, false, &ok);
+    if(!ok) {
+        return;
+    }
+    auto target = this->proxyManager->getAudiobookProxy(records[names.indexOf(chosenName)]);
+
+    if(this->setting->getConfirmDestructive()) {
+        auto answer = QMessageBox::question(this->mainWindow, "Merge Audiobooks",
+                                            "Move the files to " + chosenName +
+                                            " and remove this audiobook? The files stay where they are on disk.");
+        if(answer != QMessageBox::Yes) {
+            return;
+        }
+    }
+
+    target->mergeFrom(audiobook);
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleSetCover(std::shared_ptr<AudiobookProxy> audiobook) {
+    auto imagePath = QFileDialog::getOpenFileName(this->mainWindow, "Choose a Cover", audiobook->getPath(),
+                                                  "Images (*.png *.jpg *.jpeg *.bmp *.gif)");
+    if(imagePath.isEmpty()) {
+        return;
+    }
+
+    QString errorMessage;
+    if(!audiobook->setCoverOverride(imagePath, &errorMessage)) {
+        QMessageBox::warning(this->mainWindow, "Set Cover", "The image can't be used as a cover: " + errorMessage);
+        return;
+    }
+
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleResetCover(std::shared_ptr<AudiobookProxy> audiobook) {
+    audiobook->setCoverOverride(QString());
+    this->refreshViews();
+}
+
+void AudiobookListViewHandler::handleVerifyIntegrity(std::shared_ptr<AudiobookProxy> audiobook) {
+    auto name = QDir(audiobook->getPath()).dirName();
+    auto task = new Core::VerifyIntegrityTask(audiobook->getFilesForAudiobook(), [this](int checked, int total) {
+        emit this->integrityProgressed(checked, total);
+    }, [this, name](QStringList problems) {
+        emit this->integrityProgressed(0, 0);
+        emit this->integrityChecked(name, problems);
+    });
+    QThreadPool::globalInstance()->start(task);
+}
+
+void AudiobookListViewHandler::showIntegrityResult(QString audiobookName, QStringList problems) {
+    this->refreshViews();
+
+    if(problems.isEmpty()) {
+        QMessageBox::information(this->mainWindow, "Verify Files",
+                                 "All files of " + audiobookName + " match their checksums.");
+        return;
+    }
+
+    QMessageBox::warning(this->mainWindow, "Verify Files",
+                         QString::number(problems.size()) + " files of " + audiobookName +
+                         " failed the check:\n\n" + problems.join("\n"));
+}
+
+void AudiobookListViewHandler::handleRemoveMissingFiles(std::shared_ptr<AudiobookProxy> audiobook) {
+    auto missingCount = audiobook->getMissingFiles().size();
+    if(missingCount == 0) {
+        QMessageBox::information(this->mainWindow, "Remove Missing Files", "All files of this audiobook were found.");
+        return;
+    }
+
+    auto answer = QMessageBox::question(this->mainWindow, "Remove Missing Files",
+                                        "Remove " + QString::number(missingCount) +
+                                        " files that can no longer be found from this audiobook?");
+    if(answer != QMessageBox::Yes) {
+        return;
+    }
+
+    audiobook->removeMissingFiles();
+    this->refreshViews();
+}
 
+void AudiobookListViewHandler::refreshViews() {
     // we should update the audiobook list view as well as the file list view
     auto fileModel = static_cast<FileDisplayModel*>(this->fileListView->model());
     fileModel->select();
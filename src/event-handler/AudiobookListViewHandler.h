@@ -9,6 +9,7 @@
 #include <memory>
 #include <src/proxy-objects/AudiobookProxy.h>
 #include <src/proxy-objects/ProxyManager.h>
+#include <src/core/Setting.h>
 #include <QListView>
 #include <QMenu>
 #include <QMainWindow>
@@ -21,12 +22,17 @@ class AudiobookListViewHandler: public QObject {
     QListView* audiobookListView;
     QListView* fileListView;
     std::shared_ptr<ProxyManager> proxyManager;
+    Core::Setting* setting;
+
+    // reload the audiobook and file list after an audiobook changed
+    void refreshViews();
 
 public:
     AudiobookListViewHandler(QMainWindow* window,
                              QListView *audiobookListView,
                              QListView *fileListView,
                              std::shared_ptr<ProxyManager> proxyManager,
+                             Core::Setting* setting,
                              QObject* parent = 0
     );
 
@@ -35,9 +41,24 @@ public slots:
     void handleDeleteAudiobook(std::shared_ptr<AudiobookProxy> audiobook);
     void handleMarkAsReadAudiobook(std::shared_ptr<AudiobookProxy> audiobook);
     void handleRescan(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleAddToQueue(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleRemoveMissingFiles(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleSetResumeMode(std::shared_ptr<AudiobookProxy> audiobook, AudiobookResumeMode resumeMode);
+    void handleSetFileSortMode(std::shared_ptr<AudiobookProxy> audiobook, Core::FileSortMode fileSortMode);
+    void handleRename(std::shared_ptr<AudiobookProxy> audiobook, QSqlRecord record);
+    void handleMergeInto(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleSetCover(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleResetCover(std::shared_ptr<AudiobookProxy> audiobook);
+    void handleVerifyIntegrity(std::shared_ptr<AudiobookProxy> audiobook);
+    void showIntegrityResult(QString audiobookName, QStringList problems);
 
     void contextMenuRequested(const QPoint &position);
 
+signals:
+    // emitted from the checking thread, checked and total are both 0 once it is done
+    void integrityProgressed(int checked, int total);
+    void integrityChecked(QString audiobookName, QStringList problems);
+
 };
 
 
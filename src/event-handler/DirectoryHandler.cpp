@@ -5,15 +5,18 @@
 #include <QtWidgets/QMessageBox>
 #include <src/core/AudiobookScan.h>
 #include <src/core/tasks/ScanDirectoryTask.h>
+#include <src/core/tasks/RescanAllTask.h>
 #include "DirectoryHandler.h"
 
-DirectoryHandler::DirectoryHandler(Audiobook* audiobookModel, AudiobookFile* audiobookFileModel) {
+DirectoryHandler::DirectoryHandler(Audiobook* audiobookModel, AudiobookFile* audiobookFileModel, Core::Setting* setting) {
     this->audiobookModel = audiobookModel;
     this->audiobookFileModel = audiobookFileModel;
+    this->setting = setting;
+    this->isRescanningAll = false;
 }
 
 void DirectoryHandler::handleDirectoryAdded(QSqlRecord record) {
-    auto task = new Core::ScanDirectoryTask(record, this->audiobookModel);
+    auto task = new Core::ScanDirectoryTask(record, this->audiobookModel, this->setting);
     QThreadPool::globalInstance()->start(task);
 }
 
@@ -24,6 +27,32 @@ void DirectoryHandler::handleDirectoryRemoved(QSqlRecord record) {
 }
 
 void DirectoryHandler::handleDirectoryRescan(QSqlRecord record) {
-    auto task = new Core::ScanDirectoryTask(record, this->audiobookModel);
+    if(!record.value("enabled").toBool()) {
+        return;
+    }
+
+    auto task = new Core::ScanDirectoryTask(record, this->audiobookModel, this->setting);
+    QThreadPool::globalInstance()->start(task);
+}
+
+void DirectoryHandler::handleDirectoryEnabledChanged(QSqlRecord record) {
+    this->audiobookModel->updateFilter();
+
+    // changes made while it was disabled were never picked up
+    this->handleDirectoryRescan(record);
+}
+
+void DirectoryHandler::handleDirectoryRescanAll(QList<QSqlRecord> records) {
+    if(records.isEmpty() || this->isRescanningAll.exchange(true)) {
+        return;
+    }
+
+    auto task = new Core::RescanAllTask(records, this->audiobookModel, this->setting, [this](QString directory) {
+        if(directory.isEmpty()) {
+            this->isRescanningAll = false;
+        }
+
+        emit this->rescanAllProgressed(directory);
+    });
     QThreadPool::globalInstance()->start(task);
 }
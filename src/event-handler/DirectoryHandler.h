@@ -6,19 +6,31 @@
 #define NODOKANATIVE_DIRECTORYHANDLER_H
 
 #include <QtCore>
+#include <atomic>
 #include <QtSql>
+#include <src/core/Setting.h>
 
 class DirectoryHandler : public QObject {
     Q_OBJECT
 private:
     Audiobook* audiobookModel;
     AudiobookFile* audiobookFileModel;
+    Core::Setting* setting;
+
+    // only one rescan of all directories runs at a time
+    std::atomic<bool> isRescanningAll;
 
 public:
-    DirectoryHandler(Audiobook* audiobookModel, AudiobookFile* audiobookFileModel);
+    DirectoryHandler(Audiobook* audiobookModel, AudiobookFile* audiobookFileModel, Core::Setting* setting);
     void handleDirectoryAdded(QSqlRecord record);
     void handleDirectoryRemoved(QSqlRecord record);
     void handleDirectoryRescan(QSqlRecord record);
+    void handleDirectoryEnabledChanged(QSqlRecord record);
+    void handleDirectoryRescanAll(QList<QSqlRecord> records);
+
+signals:
+    // the directory currently being rescanned by a rescan of all directories, empty when it's done
+    void rescanAllProgressed(QString directory);
 
 };
 
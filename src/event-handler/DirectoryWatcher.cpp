@@ -0,0 +1,186 @@
+#include <QDirIterator>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QThreadPool>
+#include <src/core/tasks/ScanDirectoryTask.h>
+#include "DirectoryWatcher.h"
+
+// wait for the directory to settle down before rescanning, copying a book in triggers a burst of changes
+const static int DEBOUNCE_INTERVAL_MS = 2000;
+
+DirectoryWatcher::DirectoryWatcher(Directory *directoryModel,
+                                   Audiobook *audiobookModel,
+                                   Core::Setting *setting,
+                                   std::shared_ptr<ProxyManager> proxyManager,
+                                   QObject *parent) : QObject(parent) {
+    this->directoryModel = directoryModel;
+    this->audiobookModel = audiobookModel;
+    this->setting = setting;
+    this->proxyManager = proxyManager;
+    this->enabled = false;
+
+    this->watcher = new QFileSystemWatcher(this);
+    this->debounceTimer = new QTimer(this);
+    this->debounceTimer->setSingleShot(true);
+    this->debounceTimer->setInterval(DEBOUNCE_INTERVAL_MS);
+
+    connect(this->watcher, &QFileSystemWatcher::directoryChanged,
+            this, &DirectoryWatcher::handleDirectoryChanged);
+    connect(this->debounceTimer, &QTimer::timeout,
+            this, &DirectoryWatcher::processChanges);
+}
+
+void DirectoryWatcher::setEnabled(bool enabled) {
+    if(this->enabled == enabled) {
+        return;
+    }
+    this->enabled = enabled;
+
+    if(enabled) {
+        for(int i = 0; i < this->directoryModel->rowCount(); i++) {
+            this->watchRecursively(this->directoryModel->record(i).value("full_path").toString());
+        }
+    } else {
+        this->debounceTimer->stop();
+        this->changedPaths.clear();
+
+        auto watchedPaths = this->watcher->directories();
+        if(!watchedPaths.isEmpty()) {
+            this->watcher->removePaths(watchedPaths);
+        }
+    }
+}
+
+void DirectoryWatcher::handleDirectoryAdded(QSqlRecord record) {
+    if(this->enabled) {
+        this->watchRecursively(record.value("full_path").toString());
+    }
+}
+
+void DirectoryWatcher::handleDirectoryRemoved(QSqlRecord record) {
+    this->unwatchRecursively(record.value("full_path").toString());
+}
+
+void DirectoryWatcher::handleDirectoryChanged(const QString &path) {
+    this->changedPaths.insert(path);
+    this->debounceTimer->start();
+}
+
+void DirectoryWatcher::processChanges() {
+    auto paths = this->changedPaths;
+    this->changedPaths.clear();
+
+    for(auto &path : paths) {
+        this->processChangedPath(path);
+    }
+
+    this->audiobookModel->select();
+}
+
+void DirectoryWatcher::processChangedPath(QString path) {
+    auto directoryRecord = this->findScanningDirectory(path);
+    if(directoryRecord.isEmpty()) {
+        // the scanning directory was removed in the meantime
+        return;
+    }
+
+    if(!directoryRecord.value("enabled").toBool()) {
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE directory = ?");
+    query.addBindValue(directoryRecord.value("full_path").toString());
+    if(!query.exec()) {
+        qWarning() << "audiobook lookup query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    bool insideAudiobook = false;
+    while(query.next()) {
+        auto audiobookPath = query.record().value("full_path").toString();
+        bool isParent = path == audiobookPath || path.startsWith(audiobookPath + "/");
+        bool isChild = audiobookPath.startsWith(path + "/");
+
+        if(!isParent && !isChild) {
+            continue;
+        }
+
+        auto audiobook = this->proxyManager->getAudiobookProxy(query.record());
+        if(!QDir(audiobookPath).exists()) {
+            audiobook->remove();
+        } else if(isParent) {
+            // files were added to or removed from an audiobook we already know about
+            audiobook->rescan();
+            insideAudiobook = true;
+        }
+    }
+
+    if(!QDir(path).exists()) {
+        this->unwatchRecursively(path);
+        return;
+    }
+
+    // new subdirectories have to be watched as well
+    this->watchRecursively(path);
+
+    if(!insideAudiobook) {
+        auto task = new Core::ScanDirectoryTask(directoryRecord, this->audiobookModel, this->setting, path);
+        QThreadPool::globalInstance()->start(task);
+    }
+}
+
+QSqlRecord DirectoryWatcher::findScanningDirectory(QString path) {
+    for(int i = 0; i < this->directoryModel->rowCount(); i++) {
+        auto record = this->directoryModel->record(i);
+        auto directoryPath = record.value("full_path").toString();
+
+        if(path == directoryPath || path.startsWith(directoryPath + "/")) {
+            return record;
+        }
+    }
+
+    return QSqlRecord();
+}
+
+void DirectoryWatcher::watchRecursively(QString path) {
+    if(!QDir(path).exists()) {
+        return;
+    }
+
+    QStringList paths;
+    paths << path;
+
+    QDirIterator it(path, QDir::Dirs | QDir::NoDotAndDotDot, QDirIterator::Subdirectories);
+    while(it.hasNext()) {
+        paths << it.next();
+    }
+
+    // QFileSystemWatcher complains about paths it is already watching
+    auto watchedPaths = this->watcher->directories();
+    QStringList newPaths;
+    for(auto &currentPath : paths) {
+        if(!watchedPaths.contains(currentPath)) {
+            newPaths << currentPath;
+        }
+    }
+
+    if(!newPaths.isEmpty()) {
+        this->watcher->addPaths(newPaths);
+    }
+}
+
+void DirectoryWatcher::unwatchRecursively(QString path) {
+    QStringList pathsToRemove;
+    for(auto &watchedPath : this->watcher->directories()) {
+        if(watchedPath == path || watchedPath.startsWith(path + "/")) {
+            pathsToRemove << watchedPath;
+        }
+    }
+
+    if(!pathsToRemove.isEmpty()) {
+        this->watcher->removePaths(pathsToRemove);
+    }
+}
@@ -0,0 +1,54 @@
+#ifndef NODOKANATIVE_DIRECTORYWATCHER_H
+#define NODOKANATIVE_DIRECTORYWATCHER_H
+
+#include <QObject>
+#include <QFileSystemWatcher>
+#include <QTimer>
+#include <QSet>
+#include <memory>
+#include <src/model/Directory.h>
+#include <src/model/Audiobook.h>
+#include <src/core/Setting.h>
+#include <src/proxy-objects/ProxyManager.h>
+
+/**
+ * DirectoryWatcher watches the scanning directories and rescans the part of the tree
+ * that changed, so new audiobooks show up without a manual rescan
+ */
+class DirectoryWatcher : public QObject {
+    Q_OBJECT
+
+    Directory* directoryModel;
+    Audiobook* audiobookModel;
+    Core::Setting* setting;
+    std::shared_ptr<ProxyManager> proxyManager;
+
+    QFileSystemWatcher* watcher;
+    QTimer* debounceTimer;
+    QSet<QString> changedPaths;
+    bool enabled;
+
+    // watch the directory and every directory below it
+    void watchRecursively(QString path);
+    void unwatchRecursively(QString path);
+
+    QSqlRecord findScanningDirectory(QString path);
+    void processChangedPath(QString path);
+
+public:
+    DirectoryWatcher(Directory* directoryModel,
+                     Audiobook* audiobookModel,
+                     Core::Setting* setting,
+                     std::shared_ptr<ProxyManager> proxyManager,
+                     QObject* parent = 0);
+
+public slots:
+    void setEnabled(bool enabled);
+    void handleDirectoryAdded(QSqlRecord record);
+    void handleDirectoryRemoved(QSqlRecord record);
+    void handleDirectoryChanged(const QString &path);
+    void processChanges();
+};
+
+
+#endif //NODOKANATIVE_DIRECTORYWATCHER_H
@@ -2,6 +2,10 @@
 // Created by mistlight on 2017/02/04.
 //
 
+#include <QDesktopServices>
+#include <QFileInfo>
+#include <QMessageBox>
+#include <QUrl>
 #include <src/model/FileDisplayModel.h>
 #include "FileListViewHandler.h"
 
@@ -38,9 +42,48 @@ void FileListViewHandler::contextMenuRequested(const QPoint &position) {
             this->handleRemove(fileProxy);
         });
 
+        QAction *skipAutoAdvanceAction = new QAction("Don't Auto-Play");
+        skipAutoAdvanceAction->setCheckable(true);
+        skipAutoAdvanceAction->setChecked(fileProxy->getSkipAutoAdvance());
+        connect(skipAutoAdvanceAction, &QAction::triggered, [this, fileProxy](bool checked) {
+            this->handleSkipAutoAdvance(fileProxy, checked);
+        });
+
+        // the list is shown in play order, so the row is the index of the file in the audiobook
+        int row = modelIndex.row();
+        QAction *moveUpAction = new QAction("Move Up");
+        moveUpAction->setEnabled(row > 0);
+        connect(moveUpAction, &QAction::triggered, [this, fileProxy, row]() {
+            this->handleMove(fileProxy, row, row - 1);
+        });
+
+        QAction *moveDownAction = new QAction("Move Down");
+        moveDownAction->setEnabled(row < model->rowCount() - 1);
+        connect(moveDownAction, &QAction::triggered, [this, fileProxy, row]() {
+            this->handleMove(fileProxy, row, row + 1);
+        });
+
+        QAction *resetOrderAction = new QAction("Reset File Order");
+        connect(resetOrderAction, &QAction::triggered, [this, fileProxy]() {
+            this->handleResetOrder(fileProxy);
+        });
+
+        QAction *openFolderAction = new QAction("Open Containing Folder");
+        connect(openFolderAction, &QAction::triggered, [this, fileProxy]() {
+            this->handleOpenFolder(fileProxy);
+        });
+
         QMenu *menu = new QMenu(this->window);
         menu->addAction(resetAction);
         menu->addAction(markAsReadAction);
+        menu->addAction(skipAutoAdvanceAction);
+        menu->addSeparator();
+        menu->addAction(moveUpAction);
+        menu->addAction(moveDownAction);
+        menu->addAction(resetOrderAction);
+        menu->addSeparator();
+        menu->addAction(openFolderAction);
+        menu->addSeparator();
         menu->addAction(removeAction);
         menu->exec(this->fileListView->mapToGlobal(position));
     }
@@ -56,6 +99,11 @@ void FileListViewHandler::handleMarkAsRead(std::shared_ptr<AudiobookFileProxy> f
     fileListView->update();
 }
 
+void FileListViewHandler::handleSkipAutoAdvance(std::shared_ptr<AudiobookFileProxy> file, bool skipAutoAdvance) {
+    file->setSkipAutoAdvance(skipAutoAdvance);
+    fileListView->update();
+}
+
 void FileListViewHandler::handleRemove(std::shared_ptr<AudiobookFileProxy> file) {
     file->remove();
 
@@ -65,3 +113,51 @@ void FileListViewHandler::handleRemove(std::shared_ptr<AudiobookFileProxy> file)
     fileListView->update();
 }
 
+void FileListViewHandler::handleOpenFolder(std::shared_ptr<AudiobookFileProxy> file) {
+    // the file itself may be missing, its folder is what gets opened
+    auto folderPath = QFileInfo(file->path()).absolutePath();
+    if(!QFileInfo(folderPath).isDir()) {
+        QMessageBox::warning(this->window, "Open Containing Folder",
+                             "The folder " + folderPath + " does not exist anymore.");
+        return;
+    }
+
+    if(!QDesktopServices::openUrl(QUrl::fromLocalFile(folderPath))) {
+        QMessageBox::warning(this->window, "Open Containing Folder",
+                             "No file manager could be started to open " + folderPath + ".");
+    }
+}
+
+std::shared_ptr<AudiobookProxy> FileListViewHandler::audiobookForFile(std::shared_ptr<AudiobookFileProxy> file) {
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE id = ?");
+    query.addBindValue(file->getRecord().value("audiobook_id").toInt());
+    if(!query.exec() || !query.next()) {
+        return nullptr;
+    }
+
+    return this->manager->getAudiobookProxy(query.record());
+}
+
+void FileListViewHandler::handleMove(std::shared_ptr<AudiobookFileProxy> file, int from, int to) {
+    auto audiobook = this->audiobookForFile(file);
+    if(audiobook == nullptr || !audiobook->moveFile(from, to)) {
+        return;
+    }
+
+    auto model = static_cast<FileDisplayModel*>(fileListView->model());
+    model->select();
+    fileListView->update();
+}
+
+void FileListViewHandler::handleResetOrder(std::shared_ptr<AudiobookFileProxy> file) {
+    auto audiobook = this->audiobookForFile(file);
+    if(audiobook == nullptr) {
+        return;
+    }
+    audiobook->resetFileOrder();
+
+    auto model = static_cast<FileDisplayModel*>(fileListView->model());
+    model->select();
+    fileListView->update();
+}
@@ -20,6 +20,8 @@ private:
     QListView* fileListView;
     std::shared_ptr<ProxyManager> manager;
 
+    std::shared_ptr<AudiobookProxy> audiobookForFile(std::shared_ptr<AudiobookFileProxy> file);
+
 public:
     FileListViewHandler(
         QMainWindow* window,
@@ -34,6 +36,10 @@ public slots:
     void handleReset(std::shared_ptr<AudiobookFileProxy> file);
     void handleMarkAsRead(std::shared_ptr<AudiobookFileProxy> file);
     void handleRemove(std::shared_ptr<AudiobookFileProxy> file);
+    void handleSkipAutoAdvance(std::shared_ptr<AudiobookFileProxy> file, bool skipAutoAdvance);
+    void handleMove(std::shared_ptr<AudiobookFileProxy> file, int from, int to);
+    void handleResetOrder(std::shared_ptr<AudiobookFileProxy> file);
+    void handleOpenFolder(std::shared_ptr<AudiobookFileProxy> file);
 };
 
 
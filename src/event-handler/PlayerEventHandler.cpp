@@ -2,11 +2,21 @@
 // Created by mistlight on 1/7/17.
 //
 
+#include <algorithm>
+#include <src/core/Util.h>
 #include "PlayerEventHandler.h"
 
-Core::PlayerEventHandler::PlayerEventHandler(Core::ConcretePlayer *concretePlayer, QWidget *mainWindow) {
+// the position is reported at least every couple of seconds while playing, anything longer isn't listening time
+const static qint64 MAX_LISTENED_TICK_MS = 5000;
+
+Core::PlayerEventHandler::PlayerEventHandler(Core::ConcretePlayer *concretePlayer,
+                                             Core::Setting *setting,
+                                             QWidget *mainWindow) {
     this->concretePlayer = concretePlayer;
+    this->setting = setting;
     this->mainWindow = mainWindow;
+    this->unsavedTime = 0;
+    this->unsavedListenedTime = 0;
 
     this->setupPlayerCallbacks();
 }
@@ -16,25 +26,79 @@ void Core::PlayerEventHandler::setupPlayerCallbacks() {
     connect(this->concretePlayer, &ConcretePlayer::stateChanged, [this](libvlc_state_t newState) {
         auto abFile = this->concretePlayer->getAudiobookFile();
 
+        notifyPlayerLoading(libvlc_Opening == newState || libvlc_Buffering == newState);
+
         if (libvlc_Playing == newState) {
             abFile->setAsCurrent();
 
+            {
+                QMutexLocker locker(&this->progressMutex);
+                this->listenTimer.restart();
+            }
+
             notifyPlayerState(abFile, true);
         } else if (libvlc_Stopped == newState || libvlc_Paused == newState) {
-            abFile->saveCurrentTime(this->concretePlayer->getCurrentTime());
+            {
+                QMutexLocker locker(&this->progressMutex);
+                this->countListenedTime(abFile);
+                this->listenTimer.invalidate();
+                this->saveListenedTime();
+            }
+
+            auto currentTime = this->concretePlayer->getCurrentTime();
+            this->saveProgress(abFile, currentTime);
+
+            // stopping just before the end shouldn't leave the file at 99%
+            if(abFile->getCompleteness() < 100 &&
+               Core::isNearEnd(currentTime, abFile->getMediaDuration(),
+                               this->setting->getCompleteToleranceSeconds(),
+                               this->setting->getCompleteTolerancePercent())) {
+                abFile->setAsComplete();
+            }
 
             notifyPlayerState(abFile, false);
         } else if (libvlc_Ended == newState) {
             concretePlayer->releaseMedia();
+        } else if (libvlc_Opening == newState || libvlc_Buffering == newState) {
+            // the play button shows the loading state instead, leave it as it was
         } else {
             notifyPlayerState(abFile, false);
         }
     });
 
     connect(this->concretePlayer, &ConcretePlayer::timeProgressed, [this](libvlc_time_t time) {
-        notifyPlayerTime(this->concretePlayer->getAudiobookFile(), time);
+        auto abFile = this->concretePlayer->getAudiobookFile();
+        notifyPlayerTime(abFile, time);
+
+        // the length wasn't known when the media was parsed, ask again now that it is playing
+        if(abFile->isPropertyParsed() && abFile->getMediaDuration() <= 0) {
+            auto length = this->concretePlayer->getLengthInMs();
+            if(length > 0) {
+                abFile->setProperty(MediaProperty(length));
+                notifyMediaParsed(abFile);
+            }
+        }
 
-        this->concretePlayer->getAudiobookFile()->saveCurrentTime(time);
+        QMutexLocker locker(&this->progressMutex);
+
+        this->countListenedTime(abFile);
+
+        // the player moved on to another file, the last position of the previous one still has to be kept
+        if(this->unsavedFile != nullptr && this->unsavedFile != abFile) {
+            this->unsavedFile->saveCurrentTime(this->unsavedTime);
+            this->unsavedFile = nullptr;
+        }
+
+        auto saveInterval = this->setting->getProgressSaveInterval() * 1000LL;
+        if(!this->lastSaveTimer.isValid() || this->lastSaveTimer.elapsed() >= saveInterval) {
+            abFile->saveCurrentTime(time);
+            this->saveListenedTime();
+            this->unsavedFile = nullptr;
+            this->lastSaveTimer.restart();
+        } else {
+            this->unsavedFile = abFile;
+            this->unsavedTime = time;
+        }
     });
 
     connect(this->concretePlayer, &ConcretePlayer::parsedStatusChanged, [this](bool isParsed) {
@@ -50,3 +114,52 @@ void Core::PlayerEventHandler::setupPlayerCallbacks() {
     });
 }
 
+
+void Core::PlayerEventHandler::saveProgress(std::shared_ptr<AudiobookFileProxy> file, long long time) {
+    QMutexLocker locker(&this->progressMutex);
+
+    if(this->unsavedFile != nullptr && this->unsavedFile != file) {
+        this->unsavedFile->saveCurrentTime(this->unsavedTime);
+    }
+
+    file->saveCurrentTime(time);
+    this->unsavedFile = nullptr;
+    this->lastSaveTimer.restart();
+}
+
+void Core::PlayerEventHandler::flushProgress() {
+    QMutexLocker locker(&this->progressMutex);
+
+    if(this->unsavedFile != nullptr) {
+        this->unsavedFile->saveCurrentTime(this->unsavedTime);
+        this->unsavedFile = nullptr;
+    }
+
+    this->countListenedTime(this->concretePlayer->getAudiobookFile());
+    this->saveListenedTime();
+}
+
+void Core::PlayerEventHandler::countListenedTime(std::shared_ptr<AudiobookFileProxy> file) {
+    if(!this->listenTimer.isValid() || file == nullptr) {
+        return;
+    }
+
+    // a long gap between ticks means the computer was asleep, not that anyone was listening
+    auto elapsed = std::min(this->listenTimer.restart(), MAX_LISTENED_TICK_MS);
+
+    // the time belongs to the audiobook that was playing when it was counted
+    if(this->listenedFile != nullptr && this->listenedFile != file) {
+        this->saveListenedTime();
+    }
+
+    this->listenedFile = file;
+    this->unsavedListenedTime += elapsed;
+}
+
+void Core::PlayerEventHandler::saveListenedTime() {
+    if(this->listenedFile != nullptr) {
+        this->listenedFile->addListenedTime(this->unsavedListenedTime);
+    }
+
+    this->unsavedListenedTime = 0;
+}
@@ -6,6 +6,8 @@
 #define NODOKANATIVE_PLAYEREVENTHANDLER_H
 
 
+#include <QElapsedTimer>
+#include <QMutex>
 #include <src/ui-element/MainWindow.h>
 #include "src/core/ConcretePlayer.h"
 
@@ -14,17 +16,41 @@ namespace Core {
     Q_OBJECT
 
         ConcretePlayer* concretePlayer;
+        Setting* setting;
         QWidget* mainWindow;
 
+        // the latest position that hasn't been saved yet, the ticks come in far more often than it is saved
+        QMutex progressMutex;
+        std::shared_ptr<AudiobookFileProxy> unsavedFile;
+        long long unsavedTime;
+        QElapsedTimer lastSaveTimer;
+
+        // wall clock time spent playing, so speed changes and listening to a part again are counted as they happened
+        QElapsedTimer listenTimer;
+        std::shared_ptr<AudiobookFileProxy> listenedFile;
+        long long unsavedListenedTime;
+
+        void saveProgress(std::shared_ptr<AudiobookFileProxy> file, long long time);
+
+        // both expect the progress mutex to be held
+        void countListenedTime(std::shared_ptr<AudiobookFileProxy> file);
+        void saveListenedTime();
+
     public:
-        PlayerEventHandler(ConcretePlayer *concretePlayer, QWidget* mainWindow);
+        PlayerEventHandler(ConcretePlayer *concretePlayer, Setting* setting, QWidget* mainWindow);
 
         void setupPlayerCallbacks();
 
+        // save the position that is held back by the throttling, e.g. when the app is closing
+        void flushProgress();
+
     signals:
         // when the play state of the media player changes
         void notifyPlayerState(std::shared_ptr<AudiobookFileProxy> file, bool isPlaying);
 
+        // when the media player starts or stops opening/buffering the current media file
+        void notifyPlayerLoading(bool isLoading);
+
         // when there is progression with the current media file
         void notifyPlayerTime(std::shared_ptr<AudiobookFileProxy> file, double currentTime);
 
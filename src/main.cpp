@@ -4,30 +4,77 @@
 #include <src/core/ConcretePlayer.h>
 #include <QtWidgets/QMessageBox>
 #include <src/core/NodokaApp.h>
+#include <src/core/Util.h>
 #include <src/simple-lib/RunGuard.h>
+#include <src/core/LogBuffer.h>
+#include <QCommandLineParser>
+#include <QFileInfo>
+#include <QTextStream>
 
 
 int main(int argc, char *argv[]) {
+    // installed first so the report has the messages from the start up as well
+    Core::installLogBuffer();
+
     QApplication app(argc, argv);
     app.setOrganizationName("Otakukingdom Co");
     app.setOrganizationDomain("nodoka.otakukingdom.com");
     app.setApplicationName("Nodoka");
 
-    RunGuard guard("Z0DWjf33Am1YeCUdIW7h0vSxjU2RJjZcUqzgG ver0.0.2a");
+    // parse the command line before anything else, so a bad path never opens the window
+    QCommandLineParser parser;
+    parser.setApplicationDescription("Nodoka audiobook player");
+    parser.addHelpOption();
+
+    QCommandLineOption addDirOption("add-dir", "Add <directory> to the scanning directories.", "directory");
+    parser.addOption(addDirOption);
+    parser.addPositionalArgument("path", "Audiobook file or directory to open.", "[path]");
+    parser.process(app);
+
+    QTextStream errorStream(stderr);
+    auto directoriesToAdd = parser.values(addDirOption);
+    for(auto &directory : directoriesToAdd) {
+        if(!QFileInfo(directory).isDir()) {
+            errorStream << "nodoka: directory not found: " << directory << endl;
+            return EXIT_FAILURE;
+        }
+    }
+
+    QString pathToOpen;
+    if(!parser.positionalArguments().isEmpty()) {
+        pathToOpen = parser.positionalArguments().first();
+        if(!QFileInfo(pathToOpen).exists()) {
+            errorStream << "nodoka: file not found: " << pathToOpen << endl;
+            return EXIT_FAILURE;
+        }
+    }
+
+    QString dataDirError;
+    if(!Core::checkSettingPath(&dataDirError)) {
+        QMessageBox::critical(0, "Error", dataDirError);
+        return EXIT_FAILURE;
+    }
+
+    // one instance per data directory, two instances sharing the same database would overwrite each other
+    RunGuard guard("Z0DWjf33Am1YeCUdIW7h0vSxjU2RJjZcUqzgG ver0.0.2a" + Core::getSettingPath());
 
 
     if(!guard.tryToRun()) {
         QMessageBox *messageBox = new QMessageBox();
-        messageBox->critical(0, "Error", "Cannot launch multiple instances of Nodoka Player");
+        messageBox->critical(0, "Error", "Nodoka Player is already running with the data directory " +
+                                         Core::getSettingPath() + ".\n\n"
+                                         "To open a separate library in a second window, start it with "
+                                         "NODOKA_DATA_DIR set to another directory.");
 
         return EXIT_FAILURE;
     }
 
 
 
-    if(!Core::openDb()) {
+    QString databaseError;
+    if(!Core::openDb(&databaseError)) {
         QMessageBox *messageBox = new QMessageBox();
-        messageBox->critical(0, "Error", "Failed to load the config file");
+        messageBox->critical(0, "Error", "Failed to open the library database: " + databaseError);
 
         // since we failed to load the db, we shouldn't continue
         return EXIT_FAILURE;
@@ -35,7 +82,20 @@ int main(int argc, char *argv[]) {
         Core::NodokaApp nodokaApp(&app);
 
         nodokaApp.start();
-    }
 
-    return app.exec();
+        auto recoveryNotice = Core::getDatabaseRecoveryNotice();
+        if(!recoveryNotice.isEmpty()) {
+            QMessageBox::warning(0, "Database Recovered", recoveryNotice);
+        }
+
+        for(auto &directory : directoriesToAdd) {
+            nodokaApp.addScanningDirectory(directory);
+        }
+
+        if(!pathToOpen.isEmpty()) {
+            nodokaApp.openPath(pathToOpen);
+        }
+
+        return app.exec();
+    }
 }
@@ -5,11 +5,21 @@
 #include <QtSql>
 #include <QtWidgets/QMessageBox>
 #include <QDebug>
+#include <QFileInfo>
+#include <algorithm>
+#include <src/core/AudiobookScan.h>
+#include <src/core/Util.h>
+#include <src/core/DuplicateScan.h>
+#include <src/core/LibraryExport.h>
 #include "Audiobook.h"
 
+// how long an audiobook that hasn't been played is marked as new
+const static int NEW_AUDIOBOOK_DAYS = 7;
+
 Audiobook::Audiobook(AudiobookFile* audiobookFileModel,
                      std::shared_ptr<ProxyManager> proxyManager,
                      Core::ScanPlayer* scanPlayer,
+                     Core::Setting* setting,
                      QObject *parent) : QSqlTableModel(parent) {
     this->setTable("audiobooks");
     this->setEditStrategy(EditStrategy::OnManualSubmit);
@@ -17,11 +27,44 @@ Audiobook::Audiobook(AudiobookFile* audiobookFileModel,
 
     this->audiobookFile = audiobookFileModel;
     this->scanPlayer = scanPlayer;
+    this->setting = setting;
+    this->sortMode = static_cast<AudiobookSortMode>(setting->getSortMode());
+    this->collectionId = setting->getCollectionFilter();
+    this->searchQuery = setting->getSearchQuery();
 
-    this->select();
+    this->updateFilter();
+}
+
+// true if every file already belongs to an audiobook, e.g. the folder of an audiobook that was merged into another
+static bool allFilesRegistered(const QList<QString>& filePaths) {
+    for(auto &filePath : filePaths) {
+        QSqlQuery query;
+        query.prepare("SELECT full_path FROM audiobook_file WHERE full_path = ?");
+        query.addBindValue(filePath);
+        if(!query.exec() || !query.next()) {
+            return false;
+        }
+    }
+
+    return true;
 }
 
 void Audiobook::registerAudiobook(QSqlRecord baseDirectoryRecord, std::shared_ptr<QDir> directory) {
+    // the user merged this copy into another audiobook
+    if(Core::isMergedDuplicate(directory->path())) {
+        return;
+    }
+
+    // registering it would only leave an empty audiobook behind
+    auto filePaths = Core::getAllFiles(directory);
+    if(!filePaths.isEmpty() && allFilesRegistered(filePaths)) {
+        return;
+    }
+
+    this->insertAudiobook(baseDirectoryRecord, directory);
+}
+
+void Audiobook::insertAudiobook(QSqlRecord baseDirectoryRecord, std::shared_ptr<QDir> directory) {
     AudiobookRecord record(directory->path(), false);
 
     // since baseDirectoryRecord could be empty... we only set the directory field conditionally
@@ -29,10 +72,11 @@ void Audiobook::registerAudiobook(QSqlRecord baseDirectoryRecord, std::shared_pt
         record.setValue("directory", baseDirectoryRecord.value("full_path").toString());
     }
 
-    // completeness is deprecated
+    // completeness is tracked by AudiobookProxy, the column only mirrors it for sorting
     record.setValue("completeness", 0);
     record.setValue("default_order", 0);
     record.setNull("selected_file");
+    record.setValue("created_at", QDateTime::currentDateTimeUtc());
 
     this->insertRecord(-1, record);
 
@@ -56,9 +100,125 @@ void Audiobook::registerAudiobook(QSqlRecord baseDirectoryRecord, std::shared_pt
     if(!res || !hasNext) {
         QMessageBox::critical(0, "Error", "Get audiobook id of recently added audiobook failed");
     }
-    int audiobookId = query.record().value("id").toInt();
+    auto audiobookRecord = query.record();
+    int audiobookId = audiobookRecord.value("id").toInt();
+
+    this->audiobookFile->registerAudioBook(audiobookId, directory, this->setting->getFileSortMode());
+
+    // a library import may have been waiting for this audiobook
+    Core::applyPendingImport(this->proxyManager, audiobookRecord);
+}
+
+int Audiobook::combineDiscAudiobooks() {
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM audiobooks")) {
+        qWarning() << "audiobook list query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return 0;
+    }
+
+    // audiobooks that are a single disc, grouped by the folder their discs are in
+    QMap<QString, std::vector<QSqlRecord>> discGroups;
+    while(query.next()) {
+        auto record = query.record();
+        auto directory = std::shared_ptr<QDir>(new QDir(record.value("full_path").toString()));
+        if(Core::isDiscDirectory(directory)) {
+            discGroups[QFileInfo(directory->path()).absolutePath()].push_back(record);
+        }
+    }
+
+    int combinedCount = 0;
+    for(auto it = discGroups.begin(); it != discGroups.end(); ++it) {
+        auto discs = it.value();
+        if(discs.size() < 2) {
+            continue;
+        }
+
+        // the same rule the scan uses, every subdirectory has to be a disc
+        QDir parentDirectory(it.key());
+        std::vector<std::shared_ptr<QDir>> subdirectories;
+        for(auto &name : parentDirectory.entryList(QDir::Dirs | QDir::NoDotAndDotDot)) {
+            subdirectories.push_back(std::shared_ptr<QDir>(new QDir(parentDirectory.filePath(name))));
+        }
+        if(!Core::checkDirectorysimilarity(subdirectories, true)) {
+            continue;
+        }
+
+        std::sort(discs.begin(), discs.end(), [](const QSqlRecord& first, const QSqlRecord& second) {
+            return Core::naturalLessThan(first.value("full_path").toString(), second.value("full_path").toString());
+        });
+
+        QSqlQuery parentQuery;
+        parentQuery.prepare("SELECT * FROM audiobooks WHERE full_path = ?");
+        parentQuery.addBindValue(it.key());
+        if(!parentQuery.exec()) {
+            qWarning() << "audiobook lookup query failed: " << parentQuery.lastError().databaseText();
+            continue;
+        }
+
+        if(!parentQuery.next()) {
+            // the discs already own every file, so this only adds the audiobook itself
+            QSqlQuery directoryQuery;
+            directoryQuery.prepare("SELECT * FROM directories WHERE full_path = ?");
+            directoryQuery.addBindValue(discs.front().value("directory"));
+            QSqlRecord directoryRecord;
+            if(directoryQuery.exec() && directoryQuery.next()) {
+                directoryRecord = directoryQuery.record();
+            }
+            this->insertAudiobook(directoryRecord, std::shared_ptr<QDir>(new QDir(it.key())));
+
+            if(!parentQuery.exec() || !parentQuery.next()) {
+                continue;
+            }
+        }
+        auto combined = this->proxyManager->getAudiobookProxy(parentQuery.record());
+
+        // pick up where the disc that was played last left off
+        auto lastPlayed = std::max_element(discs.begin(), discs.end(), [](const QSqlRecord& first, const QSqlRecord& second) {
+            return first.value("last_played_at").toDateTime() < second.value("last_played_at").toDateTime();
+        });
+        if(!lastPlayed->value("last_played_at").isNull()) {
+            QSqlQuery updateQuery;
+            updateQuery.prepare("UPDATE audiobooks SET selected_file = ?, last_played_at = ? WHERE id = ?");
+            updateQuery.addBindValue(lastPlayed->value("selected_file"));
+            updateQuery.addBindValue(lastPlayed->value("last_played_at"));
+            updateQuery.addBindValue(combined->getId());
+            if(!updateQuery.exec()) {
+                qWarning() << "audiobook selected file update failed: " << updateQuery.lastError().databaseText();
+            }
+        }
+
+        for(auto &disc : discs) {
+            combined->mergeFrom(this->proxyManager->getAudiobookProxy(disc));
+        }
+
+        // the discs were appended one after the other, sort them like a freshly scanned audiobook instead
+        combined->resetFileOrder();
+        combinedCount++;
+    }
+
+    this->select();
+    return combinedCount;
+}
+
+void Audiobook::registerAudiobookIfNew(QSqlRecord baseDirectoryRecord, QString path) {
+    // an audiobook that is already registered picks up new files through its own rescan
+    QSqlQuery query;
+    query.prepare("SELECT id FROM audiobooks WHERE full_path = ?");
+    query.addBindValue(path);
+    if(!query.exec()) {
+        qWarning() << "audiobook lookup query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    if(query.next()) {
+        return;
+    }
 
-    this->audiobookFile->registerAudioBook(audiobookId, directory);
+    this->registerAudiobook(baseDirectoryRecord, std::shared_ptr<QDir>(new QDir(path)));
 }
 
 void Audiobook::removeAudiobookByBase(QString path) {
@@ -98,7 +258,47 @@ QVariant Audiobook::data(const QModelIndex &index, int role) const {
 
 
         auto name = currentRecord.value("name").toString();
+        QString authorDisplayString = "";
+        if(this->setting->getPreferTags()) {
+            if(!currentRecord.value("title").isNull()) {
+                name = currentRecord.value("title").toString();
+            }
+            if(!currentRecord.value("author").isNull()) {
+                authorDisplayString = "<span class=\"author\">" + currentRecord.value("author").toString() + "</span><br>";
+            }
+        }
+        // a name the user picked wins over both the folder name and the tags
+        if(!currentRecord.value("display_name").isNull()) {
+            name = currentRecord.value("display_name").toString();
+        }
+
         auto progress = QString::number(proxyRecord->getCompleteness());
+        QString finishedDisplayString = "";
+        if(proxyRecord->getCompleteness() >= 100) {
+            finishedDisplayString = "<span class=\"finished\">Finished</span>  ";
+        }
+
+        // recently added and not touched yet
+        QString newDisplayString = "";
+        auto createdAt = currentRecord.value("created_at").toDateTime();
+        if(createdAt.isValid() && currentRecord.value("last_played_at").isNull() &&
+           createdAt.daysTo(QDateTime::currentDateTimeUtc()) < NEW_AUDIOBOOK_DAYS) {
+            newDisplayString = "<span class=\"new\">New</span>  ";
+        }
+
+        QString gapsDisplayString = "";
+        if(currentRecord.value("has_gaps").toBool()) {
+            gapsDisplayString = "<span class=\"gaps\">Files missing?</span>  ";
+        }
+
+        // wall clock time, so it can be more or less than the length depending on speed and re-listening
+        QString listenedDisplayString = "";
+        auto listenedMs = currentRecord.value("listened_ms").toLongLong();
+        if(listenedMs >= 60000) {
+            listenedDisplayString = "<span class=\"progress\">Time spent: " +
+                    QString::number(listenedMs / 3600000) + "h " +
+                    QString::number((listenedMs / 60000) % 60) + "m</span>";
+        }
 
         auto length = Core::convertTimeToString(proxyRecord->getDuration());
         QString lengthDisplayString = "";
@@ -106,15 +306,114 @@ QVariant Audiobook::data(const QModelIndex &index, int role) const {
             lengthDisplayString += "<span style=\"font-weight: bold;\">" + length + "</span>  ";
         }
 
-        auto label = "<div class=\"item\"><span class=\"name\">" +
+        QString coverDisplayString = "";
+        auto coverPath = proxyRecord->getCoverPath();
+        if(!coverPath.isEmpty()) {
+            coverDisplayString = "<img class=\"cover\" src=\"" + QUrl::fromLocalFile(coverPath).toString() +
+                    "\" width=\"64\" align=\"left\">";
+        }
+
+        auto label = "<div class=\"item\">" + coverDisplayString + "<span class=\"name\">" +
                 name +
-                "</span><br>" + lengthDisplayString +
-                "<span class=\"progress\">Progress: " + progress + "% </span>" +
+                "</span><br>" + authorDisplayString + lengthDisplayString + newDisplayString + finishedDisplayString + gapsDisplayString +
+                "<span class=\"progress\">Progress: " + progress + "% </span>  " + listenedDisplayString +
                 "</div>"
         ;
         return label;
     }
 
+    if(role == Qt::ToolTipRole) {
+        if(this->record(index.row()).value("has_gaps").toBool()) {
+            return "The file numbering skips a number, a file may be missing";
+        }
+    }
+
     return QSqlTableModel::data(index, role);
 }
 
+void Audiobook::setSearchQuery(QString query) {
+    this->searchQuery = query.trimmed();
+    this->setting->setSearchQuery(this->searchQuery);
+    this->updateFilter();
+}
+
+QString Audiobook::getSearchQuery() {
+    return this->searchQuery;
+}
+
+bool Audiobook::isFiltered() {
+    return !this->searchQuery.isEmpty() || this->collectionId != -1;
+}
+
+void Audiobook::setCollectionFilter(int collectionId) {
+    this->collectionId = collectionId;
+    this->setting->setCollectionFilter(collectionId);
+    this->updateFilter();
+}
+
+int Audiobook::getCollectionFilter() {
+    return this->collectionId;
+}
+
+void Audiobook::updateFilter() {
+    QStringList conditions;
+
+    if(!this->searchQuery.isEmpty()) {
+        // setFilter only takes a raw where clause, so the query has to be escaped by hand
+        QString escaped = this->searchQuery;
+        escaped.replace("\\", "\\\\");
+        escaped.replace("%", "\\%");
+        escaped.replace("_", "\\_");
+        escaped.replace("'", "''");
+
+        QString pattern = "'%" + escaped + "%' ESCAPE '\\'";
+        conditions << "(name LIKE " + pattern +
+                      " OR display_name LIKE " + pattern +
+                      " OR title LIKE " + pattern +
+                      " OR author LIKE " + pattern + ")";
+    }
+
+    if(this->collectionId != -1) {
+        conditions << "directory IN (SELECT full_path FROM directories WHERE collection_id = " +
+                      QString::number(this->collectionId) + ")";
+    }
+
+    // audiobooks of disabled directories stay in the database, they are just not listed
+    conditions << "(directory IS NULL OR directory NOT IN (SELECT full_path FROM directories WHERE enabled = 0))";
+
+    this->setFilter(conditions.join(" AND "));
+    this->select();
+}
+
+void Audiobook::setSortMode(AudiobookSortMode sortMode) {
+    this->sortMode = sortMode;
+    this->setting->setSortMode(sortMode);
+
+    this->select();
+}
+
+AudiobookSortMode Audiobook::getSortMode() {
+    return this->sortMode;
+}
+
+QString Audiobook::orderByClause() const {
+    // ties are always broken by id so the order is stable between refreshes
+    switch(this->sortMode) {
+        case SortByTitle:
+            if(this->setting->getPreferTags()) {
+                return "ORDER BY COALESCE(display_name, title, name) COLLATE NOCASE ASC, id ASC";
+            }
+            return "ORDER BY COALESCE(display_name, name) COLLATE NOCASE ASC, id ASC";
+        case SortByRecentlyPlayed:
+            // finished audiobooks are out of the way at the bottom
+            return "ORDER BY completeness >= 100, last_played_at IS NULL, last_played_at DESC, id ASC";
+        case SortByProgress:
+            return "ORDER BY completeness DESC, id ASC";
+        case SortByRecentlyAdded:
+            // audiobooks added before created_at was filled in go last, in the order they were added
+            return "ORDER BY created_at IS NULL, created_at DESC, id DESC";
+        case SortByDateAdded:
+        default:
+            return "ORDER BY id ASC";
+    }
+}
@@ -12,15 +12,42 @@
 #include "AudiobookRecord.h"
 #include "AudiobookFile.h"
 
+/**
+ * The orders the audiobook list can be shown in, the values are persisted in the settings
+ */
+enum AudiobookSortMode {
+    SortByDateAdded = 0,
+    SortByTitle = 1,
+    SortByRecentlyPlayed = 2,
+    SortByProgress = 3,
+    SortByRecentlyAdded = 4
+};
+
 class Audiobook : public QSqlTableModel {
+    Q_OBJECT
+
     AudiobookFile* audiobookFile;
     std::shared_ptr<ProxyManager> proxyManager;
     Core::ScanPlayer* scanPlayer;
+    Core::Setting* setting;
+
+    // the list filter is built from these, the underlying table is never changed
+    QString searchQuery;
+    int collectionId;
+    AudiobookSortMode sortMode;
+    void updateFilter();
+
+    // add the audiobook row and whichever of its files aren't registered yet
+    void insertAudiobook(QSqlRecord baseDirectoryRecord, std::shared_ptr<QDir> directory);
+
+protected:
+    QString orderByClause() const;
 
 public:
     Audiobook(AudiobookFile* audiobookFileModel,
               std::shared_ptr<ProxyManager> proxyManager,
               Core::ScanPlayer *scanPlayer,
+              Core::Setting* setting,
               QObject *parent = 0);
 
     QVariant data(const QModelIndex &index, int role) const;
@@ -28,6 +55,29 @@ public:
     void registerAudiobook(QSqlRecord baseDirectoryRecord, std::shared_ptr<QDir> directory);
     void removeAudiobook(QSqlRecord record);
     void removeAudiobookByBase(QString path);
+
+    // fold audiobooks that are single "Disc N" folders into one audiobook for the folder they are in,
+    // for libraries scanned before disc folders were combined, returns how many audiobooks were made
+    int combineDiscAudiobooks();
+
+    // only show audiobooks whose name, title or author contains the query, an empty query shows all
+    void setSearchQuery(QString query);
+    QString getSearchQuery();
+
+    // the search or a collection hides some of the audiobooks
+    bool isFiltered();
+
+    // only show audiobooks from the directories of a collection, -1 shows all
+    void setCollectionFilter(int collectionId);
+    int getCollectionFilter();
+
+    void setSortMode(AudiobookSortMode sortMode);
+    AudiobookSortMode getSortMode();
+
+public slots:
+    // register the folder at path unless it is already an audiobook, scans call this through the ui thread
+    // since the model and the database connection belong to it
+    void registerAudiobookIfNew(QSqlRecord baseDirectoryRecord, QString path);
 };
 
 
@@ -4,8 +4,11 @@
 
 #include <QtCore/QDirIterator>
 #include <src/core/AudiobookScan.h>
+#include <src/core/Setting.h>
+#include <src/core/Util.h>
 #include <QtWidgets/QMessageBox>
 #include "AudiobookFileRecord.h"
+#include <algorithm>
 
 
 AudiobookFile::AudiobookFile(QObject *parent) : QSqlTableModel(parent) {
@@ -33,8 +36,18 @@ void AudiobookFile::addAudiobookFile(int audiobookId, int position, QString path
     }
 }
 
-void AudiobookFile::registerAudioBook(int audiobookId, std::shared_ptr<QDir> directory) {
+void AudiobookFile::registerAudioBook(int audiobookId, std::shared_ptr<QDir> directory, int fileSortMode) {
     QList<QString> filePathList = Core::getAllFiles(directory);
+    if(fileSortMode == Core::FileSortModified) {
+        // the files of a combined audiobook stay grouped by disc
+        std::stable_sort(filePathList.begin(), filePathList.end(), [](const QString& first, const QString& second) {
+            auto directoryOrder = Core::compareParentDirectory(first, second);
+            if(directoryOrder != 0) {
+                return directoryOrder < 0;
+            }
+            return Core::modifiedEarlier(first, second);
+        });
+    }
 
     int position = 1;
     for(auto &currentPath : filePathList) {
@@ -17,7 +17,8 @@ private:
 public:
 
     AudiobookFile(QObject *parent = 0);
-    void registerAudioBook(int audiobookId, std::shared_ptr<QDir> directory);
+    // fileSortMode is one of Core::FileSortMode, track numbers aren't known yet so they are sorted by name
+    void registerAudioBook(int audiobookId, std::shared_ptr<QDir> directory, int fileSortMode);
     static void removeAudiobook(int audiobookId);
     void addAudiobookFile(int audiobookId, int position, QString path);
 
@@ -2,6 +2,7 @@
 // Created by mistlight on 1/1/2017.
 //
 
+#include <src/core/Collections.h>
 #include "Directory.h"
 
 Directory::Directory(QObject *parent) : QSqlTableModel(parent) {
@@ -32,6 +33,7 @@ void Directory::addDirectory(QString path) {
 
     record.setValue("full_path", path);
     record.setValue("created_at", now);
+    record.setValue("enabled", 1);
 
     this->insertRecord(-1, record);
     auto res = this->submitAll();
@@ -61,6 +63,91 @@ void Directory::removeDirectory(QModelIndex index) {
     }
 
     emit directoryRemove(record);
+
+    // the directory may have been the last one of its collection
+    if(!record.value("collection_id").isNull()) {
+        Core::removeUnusedCollections();
+        emit collectionsChanged();
+    }
+}
+
+void Directory::setDirectoryEnabled(QModelIndex index, bool enabled) {
+    int row = index.row();
+
+    this->setData(this->index(row, this->fieldIndex("enabled")), enabled ? 1 : 0);
+    auto res = this->submitAll();
+
+    if(!res) {
+        auto errorObj = this->lastError();
+        QMessageBox *messageBox = new QMessageBox();
+        messageBox->critical(0, "Error", "Failed to write to config file, error message is: " + errorObj.driverText());
+        return;
+    }
+
+    emit directoryEnabledChanged(this->record(row));
+}
+
+bool Directory::isDirectoryEnabled(QModelIndex index) {
+    return this->record(index.row()).value("enabled").toBool();
+}
+
+void Directory::rescanAll() {
+    QList<QSqlRecord> records;
+    for(int i = 0; i < this->rowCount(); i++) {
+        auto record = this->record(i);
+        if(record.value("enabled").toBool()) {
+            records << record;
+        }
+    }
+
+    emit directoryRescanAll(records);
+}
+
+void Directory::setDirectoryCollection(QModelIndex index, int collectionId) {
+    int row = index.row();
+
+    QVariant value(QVariant::Int);
+    if(collectionId != -1) {
+        value = collectionId;
+    }
+
+    this->setData(this->index(row, this->fieldIndex("collection_id")), value);
+    auto res = this->submitAll();
+
+    if(!res) {
+        auto errorObj = this->lastError();
+        QMessageBox *messageBox = new QMessageBox();
+        messageBox->critical(0, "Error", "Failed to write to config file, error message is: " + errorObj.driverText());
+        return;
+    }
+
+    Core::removeUnusedCollections();
+    emit collectionsChanged();
+}
+
+void Directory::rescanCollection(int collectionId) {
+    QList<QSqlRecord> records;
+    for(int i = 0; i < this->rowCount(); i++) {
+        auto record = this->record(i);
+        if(record.value("enabled").toBool() && !record.value("collection_id").isNull() &&
+           record.value("collection_id").toInt() == collectionId) {
+            records << record;
+        }
+    }
+
+    emit directoryRescanAll(records);
+}
+
+QVariant Directory::data(const QModelIndex &index, int role) const {
+    auto value = QSqlTableModel::data(index, role);
+
+    if(role == Qt::DisplayRole && index.column() == this->fieldIndex("full_path")) {
+        if(!this->record(index.row()).value("enabled").toBool()) {
+            return value.toString() + " (disabled)";
+        }
+    }
+
+    return value;
 }
 
 QSqlRecord Directory::getEmptyRecord() {
@@ -81,6 +168,11 @@ QSqlRecord Directory::getEmptyRecord() {
     lastScannedField.setType(QVariant::DateTime);
     record.append(lastScannedField);
 
+    QSqlField enabledField;
+    enabledField.setName("enabled");
+    enabledField.setType(QVariant::Int);
+    record.append(enabledField);
+
     return record;
 }
 
@@ -27,10 +27,27 @@ public:
     void addDirectory(QString path);
     void removeDirectory(QModelIndex index);
 
+    // a disabled directory keeps its audiobooks and progress, but they are hidden and not rescanned
+    void setDirectoryEnabled(QModelIndex index, bool enabled);
+    bool isDirectoryEnabled(QModelIndex index);
+
+    // rescan every enabled directory, one after the other
+    void rescanAll();
+
+    // put the directory in a collection, -1 takes it out of its collection
+    void setDirectoryCollection(QModelIndex index, int collectionId);
+    // rescan the enabled directories of a collection, one after the other
+    void rescanCollection(int collectionId);
+
+    QVariant data(const QModelIndex &index, int role) const;
+
 signals:
     void directoryAdded(QSqlRecord);
     void directoryRemove(QSqlRecord);
     void directoryRescan(QSqlRecord);
+    void directoryEnabledChanged(QSqlRecord);
+    void directoryRescanAll(QList<QSqlRecord>);
+    void collectionsChanged();
 };
 
 
@@ -9,8 +9,21 @@
 #include "FileDisplayModel.h"
 
 
-FileDisplayModel::FileDisplayModel(std::shared_ptr<ProxyManager> manager, QObject *parent) : QSqlTableModel(parent) {
+// number of cells in the progress bar of a file
+const static int PROGRESS_BAR_CELLS = 10;
+
+// a text progress bar, so it lines up with the rest of the html in the row
+static QString progressBar(int completeness) {
+    auto filledCells = qBound(0, completeness * PROGRESS_BAR_CELLS / 100, PROGRESS_BAR_CELLS);
+
+    return QString("&#9646;").repeated(filledCells) + QString("&#9647;").repeated(PROGRESS_BAR_CELLS - filledCells);
+}
+
+FileDisplayModel::FileDisplayModel(std::shared_ptr<ProxyManager> manager,
+                                   Core::Setting* setting,
+                                   QObject *parent) : QSqlTableModel(parent) {
     this->manager = manager;
+    this->setting = setting;
     this->setTable("audiobook_file");
 
     auto key = this->primaryKey();
@@ -40,26 +53,75 @@ QVariant FileDisplayModel::data(const QModelIndex &index, int role) const {
 
         auto name = this->record(index.row()).
                 value("name").toString();
+        if(this->setting->getPreferTags() && !currentRecord.value("title").isNull()) {
+            name = currentRecord.value("title").toString();
+        } else if(this->setting->getChapterTitlesFromFileNames()) {
+            name = Core::chapterTitleFromFileName(name);
+        }
+        auto columns = this->setting->getFileListColumns();
         auto comepleteness = proxyEntry->getCompleteness();
         auto completenessString = QString::number(comepleteness);
         auto length = Core::convertTimeToString(proxyEntry->getMediaDuration());
 
         QString lengthDisplayString = "";
-        if(proxyEntry->getMediaDuration() > 0) {
+        if((columns & Core::FileListDuration) && proxyEntry->getMediaDuration() > 0) {
             lengthDisplayString += "<span style=\"font-weight: bold;\">" + length + "</span>  ";
         }
 
+        QString detailsDisplayString = "";
+        if(this->setting->getShowTechnicalDetails() && !proxyEntry->getCodec().isEmpty()) {
+            QStringList details;
+            details << proxyEntry->getCodec();
+            if(proxyEntry->getBitrate() > 0) {
+                details << QString::number(proxyEntry->getBitrate()) + " kbps";
+            }
+            if(proxyEntry->getSampleRate() > 0) {
+                details << QString::number(proxyEntry->getSampleRate() / 1000.0, 'g', 3) + " kHz";
+            }
+
+            detailsDisplayString = "<span class=\"details\">" + details.join(" &middot; ") + "</span>  ";
+        }
+
         QString message = "";
 
         if(!proxyEntry->fileExists()) {
             message += "(FILE NOT FOUND) ";
         }
 
+        if(proxyEntry->isScanFailed()) {
+            message += "(UNREADABLE) ";
+        }
+
+        if(proxyEntry->getIntegrity() == IntegrityChanged) {
+            message += "(CHANGED) ";
+        }
+
+        if(proxyEntry->isShort()) {
+            message += "(SHORT) ";
+        }
+
+        if(proxyEntry->getSkipAutoAdvance()) {
+            message += "(NO AUTO-PLAY) ";
+        }
+
+        QString progressDisplayString = "";
+        if(columns & Core::FileListProgressBar) {
+            progressDisplayString += "<span>" + progressBar(comepleteness) + "</span>  ";
+        }
+        if(columns & Core::FileListProgressPercent) {
+            progressDisplayString += "<span>" + completenessString + "% Completed </span>";
+        }
+
+        if((columns & Core::FileListCompleteMark) && proxyEntry->isListened()) {
+            name = "&#10003; " + name;
+        }
+
+        // hidden details are left out entirely, a file with nothing to show below its name takes one line
+        auto detailsLine = lengthDisplayString + detailsDisplayString + progressDisplayString;
         QString label = "<div class=\"file-item\"><span class=\"name\">" +
                 message +
-                name + "</span><br />" +
-                lengthDisplayString +
-                "<span>" + completenessString +"% Completed </span>" +
+                name + "</span>" +
+                (detailsLine.isEmpty() ? "" : "<br />" + detailsLine) +
                 "</div>";
 
         return label;
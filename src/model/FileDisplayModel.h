@@ -13,13 +13,14 @@
 class FileDisplayModel : public QSqlTableModel {
 
 public:
-    FileDisplayModel(std::shared_ptr<ProxyManager> manager, QObject *parent = 0);
+    FileDisplayModel(std::shared_ptr<ProxyManager> manager, Core::Setting* setting, QObject *parent = 0);
     void setSelectedAudiobook(int audiobookId);
     QModelIndex getFileIndex(QString path);
     QVariant data(const QModelIndex &index, int role) const;
 
 private:
     std::shared_ptr<ProxyManager> manager;
+    Core::Setting* setting;
     bool hasFilter;
     int selectedAudiobookId;
 
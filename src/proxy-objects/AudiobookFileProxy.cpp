@@ -9,6 +9,7 @@
 #include <src/core/Util.h>
 #include <QFile>
 #include <QFileInfo>
+#include <QElapsedTimer>
 #include <QtCore/QCryptographicHash>
 #include <src/core/tasks/ChecksumTask.h>
 
@@ -91,6 +92,77 @@ long long AudiobookFileProxy::getMediaDuration() {
     return this->mediaProperty.getDuration();
 }
 
+void AudiobookFileProxy::setMediaTags(QString title, QString artist, QString album, int trackNumber) {
+    auto nullIfEmpty = [](QString value) -> QVariant {
+        if(value.trimmed().isEmpty()) {
+            return QVariant(QVariant::String);
+        }
+
+        return value.trimmed();
+    };
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobook_file SET title = ?, artist = ?, album = ?, track_number = ? WHERE full_path = ?");
+    query.addBindValue(nullIfEmpty(title));
+    query.addBindValue(nullIfEmpty(artist));
+    query.addBindValue(nullIfEmpty(album));
+    query.addBindValue(trackNumber > 0 ? QVariant(trackNumber) : QVariant(QVariant::Int));
+    query.addBindValue(this->path());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook file tag save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    this->currentFileSetting->setValue("metadataScanned", true);
+    this->currentFileSetting->sync();
+}
+
+bool AudiobookFileProxy::isMetadataScanned() {
+    // the codec info was added later, files scanned before that need another pass
+    return this->currentFileSetting->value("metadataScanned", false).toBool() &&
+           this->currentFileSetting->contains("codec");
+}
+
+void AudiobookFileProxy::setArtworkPath(QString artworkPath) {
+    this->currentFileSetting->setValue("artworkPath", artworkPath);
+    this->currentFileSetting->sync();
+}
+
+QString AudiobookFileProxy::getArtworkPath() {
+    return this->currentFileSetting->value("artworkPath").toString();
+}
+
+void AudiobookFileProxy::setCodecInfo(QString codec, int bitrate, int sampleRate) {
+    this->currentFileSetting->setValue("codec", codec);
+    this->currentFileSetting->setValue("bitrate", bitrate);
+    this->currentFileSetting->setValue("sampleRate", sampleRate);
+    this->currentFileSetting->sync();
+}
+
+QString AudiobookFileProxy::getCodec() {
+    return this->currentFileSetting->value("codec").toString();
+}
+
+int AudiobookFileProxy::getBitrate() {
+    return this->currentFileSetting->value("bitrate", 0).toInt();
+}
+
+int AudiobookFileProxy::getSampleRate() {
+    return this->currentFileSetting->value("sampleRate", 0).toInt();
+}
+
+void AudiobookFileProxy::setScanFailed(bool scanFailed) {
+    this->currentFileSetting->setValue("scanFailed", scanFailed);
+    this->currentFileSetting->sync();
+}
+
+bool AudiobookFileProxy::isScanFailed() {
+    return this->currentFileSetting->value("scanFailed", false).toBool();
+}
+
 bool AudiobookFileProxy::isPropertyParsed() {
     if(this->mediaProperty.isNullObject()) {
         return false;
@@ -103,10 +175,11 @@ void AudiobookFileProxy::setAsCurrent() {
     if(!this->mediaProperty.isNullObject()) {
         int audiobookId = this->record.value("audiobook_id").toInt();
         auto path = this->record.value("full_path").toString();
-        QString queryString = "UPDATE audiobooks SET selected_file=? WHERE id=?";
+        QString queryString = "UPDATE audiobooks SET selected_file=?, last_played_at=? WHERE id=?";
         QSqlQuery query;
         query.prepare(queryString);
         query.addBindValue(path);
+        query.addBindValue(QDateTime::currentDateTimeUtc());
         query.addBindValue(audiobookId);
         if(!query.exec()) {
             qWarning() << "audiobook save query failed: "
@@ -135,6 +208,7 @@ void AudiobookFileProxy::saveCurrentTime(long long currentTime) {
     }
 
     this->currentFileSetting->setValue("currentTime", currentTime);
+    this->currentFileSetting->setValue("lastPlayedAt", QDateTime::currentDateTimeUtc());
     if(completeness) {
         this->currentFileSetting->setValue("completeness", calcCompleteness);
 
@@ -152,6 +226,10 @@ bool AudiobookFileProxy::currentTimeNull() {
     return this->currentFileSetting->value("currentTime").isNull();
 }
 
+QDateTime AudiobookFileProxy::getLastPlayedAt() {
+    return this->currentFileSetting->value("lastPlayedAt").toDateTime();
+}
+
 bool AudiobookFileProxy::hasNextFile() {
     int currentPosition = this->record.value("position").toInt();
     int audiobookId = this->record.value("audiobook_id").toInt();
@@ -217,8 +295,16 @@ int AudiobookFileProxy::getCompleteness() {
     return (int)round(this->currentFileSetting->value("completeness").toDouble());
 }
 
+bool AudiobookFileProxy::isListened() {
+    return this->getCompleteness() >= this->setting->getListenedThresholdPercent();
+}
+
 void AudiobookFileProxy::setAsComplete() {
     this->currentFileSetting->setValue("completeness", 100);
+
+    if(this->completenssFunctionSet) {
+        this->totalCompletenessUpdateFunction();
+    }
 }
 
 void AudiobookFileProxy::setTotalDurationUpdateFunction(std::function<void()> audiobookProxyUpdateFunction) {
@@ -228,6 +314,9 @@ void AudiobookFileProxy::setTotalDurationUpdateFunction(std::function<void()> au
 
 
 QString AudiobookFileProxy::calcCheckSum() {
+    QElapsedTimer timer;
+    timer.start();
+
     QByteArray byteArray;
     QFile f(this->path());
     if (f.open(QFile::ReadOnly)) {
@@ -237,6 +326,8 @@ QString AudiobookFileProxy::calcCheckSum() {
         }
     }
 
+    Core::logTiming("checksum", this->path(), f.size(), timer.elapsed());
+
     if(!byteArray.isNull()) {
         auto sha1Hash = byteArray.toHex();
 
@@ -256,6 +347,40 @@ void AudiobookFileProxy::calcAndWriteCheckSum(bool forced) {
     }
 }
 
+QString AudiobookFileProxy::getCheckSum() {
+    return this->currentFileSetting->value("checkSum").toString();
+}
+
+FileIntegrity AudiobookFileProxy::verifyCheckSum() {
+    FileIntegrity integrity;
+    auto savedCheckSum = this->getCheckSum();
+
+    if(!this->fileExists()) {
+        integrity = IntegrityMissing;
+    } else {
+        auto checkSum = this->calcCheckSum();
+        if(checkSum.isEmpty()) {
+            // a file that can't be read through any more is as good as damaged
+            integrity = IntegrityChanged;
+        } else if(savedCheckSum.isEmpty()) {
+            // the checksum task never got to this file, so this becomes the one to compare against
+            this->currentFileSetting->setValue("checkSum", checkSum);
+            integrity = IntegrityOk;
+        } else {
+            integrity = checkSum == savedCheckSum ? IntegrityOk : IntegrityChanged;
+        }
+    }
+
+    this->currentFileSetting->setValue("integrity", integrity);
+    this->currentFileSetting->sync();
+
+    return integrity;
+}
+
+FileIntegrity AudiobookFileProxy::getIntegrity() {
+    return static_cast<FileIntegrity>(this->currentFileSetting->value("integrity", IntegrityUnchecked).toInt());
+}
+
 void AudiobookFileProxy::setCompletenessUpdateFunction(std::function<void()> func) {
     this->completenssFunctionSet = true;
     this->totalCompletenessUpdateFunction = func;
@@ -271,6 +396,86 @@ void AudiobookFileProxy::markAsRead() {
     this->saveCurrentTime(this->getMediaDuration());
 }
 
+void AudiobookFileProxy::setPosition(int position) {
+    if(this->isNull) {
+        return;
+    }
+
+    // the cached record is used to find the next file, so it has to follow the database
+    this->record.setValue("position", position);
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobook_file SET position = ? WHERE full_path = ? AND audiobook_id = ?");
+    query.addBindValue(position);
+    query.addBindValue(this->record.value("full_path").toString());
+    query.addBindValue(this->record.value("audiobook_id").toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook file position save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+void AudiobookFileProxy::moveToAudiobook(int audiobookId, int position) {
+    if(this->isNull) {
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobook_file SET audiobook_id = ?, position = ? WHERE full_path = ?");
+    query.addBindValue(audiobookId);
+    query.addBindValue(position);
+    query.addBindValue(this->record.value("full_path").toString());
+    if(!query.exec()) {
+        qWarning() << "audiobook file move query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return;
+    }
+
+    this->record.setValue("audiobook_id", audiobookId);
+    this->record.setValue("position", position);
+}
+
+void AudiobookFileProxy::addListenedTime(long long milliseconds) {
+    if(this->isNull || milliseconds <= 0) {
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET listened_ms = COALESCE(listened_ms, 0) + ? WHERE id = ?");
+    query.addBindValue(milliseconds);
+    query.addBindValue(this->record.value("audiobook_id").toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook listened time save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+bool AudiobookFileProxy::getSkipAutoAdvance() {
+    return this->record.value("skip_auto_advance").toBool();
+}
+
+void AudiobookFileProxy::setSkipAutoAdvance(bool skipAutoAdvance) {
+    if(this->isNull) {
+        return;
+    }
+
+    this->record.setValue("skip_auto_advance", skipAutoAdvance ? 1 : 0);
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobook_file SET skip_auto_advance = ? WHERE full_path = ? AND audiobook_id = ?");
+    query.addBindValue(skipAutoAdvance ? 1 : 0);
+    query.addBindValue(this->record.value("full_path").toString());
+    query.addBindValue(this->record.value("audiobook_id").toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook file skip auto advance save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
 void AudiobookFileProxy::remove() {
     if(this->isNull) {
         return;
@@ -289,3 +494,10 @@ bool AudiobookFileProxy::fileExists() {
     this->fileExistFlag = checkFile.exists() && checkFile.isFile();
     return this->fileExistFlag;
 }
+
+bool AudiobookFileProxy::isShort() {
+    auto minimumDuration = this->setting->getMinFileDurationSeconds() * 1000LL;
+    auto duration = this->getMediaDuration();
+
+    return minimumDuration > 0 && duration > 0 && duration < minimumDuration;
+}
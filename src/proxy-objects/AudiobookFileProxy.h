@@ -14,8 +14,17 @@
 #include <src/core/Setting.h>
 #include "src/model/MediaProperty.h"
 #include <QSettings>
+#include <QDateTime>
 #include <memory>
 
+// result of the last check of a file against its saved checksum
+enum FileIntegrity {
+    IntegrityUnchecked = 0,
+    IntegrityOk = 1,
+    IntegrityChanged = 2,
+    IntegrityMissing = 3
+};
+
 class AudiobookFileProxy {
     QSharedPointer<QSettings> currentFileSetting;
     Core::Setting* setting;
@@ -36,6 +45,11 @@ public:
     AudiobookFileProxy(QSqlRecord record, Core::Setting* setting);
     AudiobookFileProxy();
     void calcAndWriteCheckSum(bool forced = false);
+    // empty until the checksum task has run for this file
+    QString getCheckSum();
+    // hash the file again and compare it to the saved checksum, the result is kept for getIntegrity
+    FileIntegrity verifyCheckSum();
+    FileIntegrity getIntegrity();
     QString path();
     QString name();
     bool getNullState();
@@ -45,8 +59,14 @@ public:
     void setAsComplete();
     long long getCurrentTime();
     bool currentTimeNull();
+    // when the current time was last saved, invalid if it never was
+    QDateTime getLastPlayedAt();
     int getCompleteness();
+    // the completeness reached the listened threshold from the settings, only used for display
+    bool isListened();
     bool fileExists();
+    // shorter than the minimum file duration, e.g. a short intro or a silence marker
+    bool isShort();
 
     bool hasNextFile();
     AudiobookFileProxy getNextFile();
@@ -58,6 +78,37 @@ public:
     void setMediaDuration(const long long duration);
     long long getMediaDuration();
 
+    // title, artist, album and track number read from the file's tags, empty values are stored as null
+    void setMediaTags(QString title, QString artist, QString album, int trackNumber);
+    bool isMetadataScanned();
+
+    // local path to the cover art vlc extracted from the file, empty if there is none
+    void setArtworkPath(QString artworkPath);
+    QString getArtworkPath();
+
+    // audio codec, bitrate in kbps and sample rate in Hz, zero when vlc doesn't report it
+    void setCodecInfo(QString codec, int bitrate, int sampleRate);
+    QString getCodec();
+    int getBitrate();
+    int getSampleRate();
+
+    // set when the scan couldn't open the file or read a duration from it, e.g. a damaged file
+    void setScanFailed(bool scanFailed);
+    bool isScanFailed();
+
+    // add time spent listening to the audiobook this file belongs to
+    void addListenedTime(long long milliseconds);
+
+    // place in the play order of the audiobook, starting at 1
+    void setPosition(int position);
+
+    // the file now belongs to another audiobook, the progress is kept by path so it stays
+    void moveToAudiobook(int audiobookId, int position);
+
+    // files like bonus interviews that playback shouldn't roll into on its own, they can still be played by hand
+    bool getSkipAutoAdvance();
+    void setSkipAutoAdvance(bool skipAutoAdvance);
+
     // direct user actions
     void resetReadStatus();
     void markAsRead();
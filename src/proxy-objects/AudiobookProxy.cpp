@@ -4,10 +4,20 @@
 
 #include "AudiobookProxy.h"
 #include <QDebug>
+#include <QSqlError>
 #include <QDir>
 #include <QDirIterator>
+#include <QFile>
 #include <src/model/AudiobookFile.h>
 #include <src/core/AudiobookScan.h>
+#include <src/core/CoverThumbnail.h>
+#include <algorithm>
+
+// size of the cover thumbnails shown in the audiobook list
+static const int COVER_THUMBNAIL_SIZE = 128;
+
+// images in the audiobook folder that are used when there is no embedded cover
+static const char* FOLDER_COVER_NAMES[] = {"cover.jpg", "cover.png", "folder.jpg", "folder.png"};
 
 AudiobookProxy::AudiobookProxy(QSqlRecord record,
                                Core::Setting *settings,
@@ -31,6 +41,9 @@ AudiobookProxy::AudiobookProxy(QSqlRecord record,
 
         auto path = Core::getUniqueSettingPath(stringToHash);
         this->currentFileSetting = QSharedPointer<QSettings>(new QSettings(path, QSettings::IniFormat));
+
+        // older versions did not mirror the completeness into the database
+        this->syncCompletenessColumn();
     }
 
     this->getFilesForAudiobook();
@@ -90,6 +103,9 @@ void AudiobookProxy::rescan() {
 
     // this will insert new files into the list, if no new files are found, then it will simply rearrange the files
     this->insertFiles(fileToInsert);
+
+    // the folder may have a new cover image
+    this->updateCover(true);
 }
 
 
@@ -185,6 +201,47 @@ void AudiobookProxy::handlePropertyScanFinished() {
     }
 
     this->setDuration(duration);
+
+    this->updateTags();
+    this->updateCover();
+
+    // the track numbers are only known after the scan, so the order may need to change
+    if(this->automaticFileSortMode() == Core::FileSortTrackTag) {
+        this->insertFiles(std::vector<QString>());
+    }
+}
+
+void AudiobookProxy::updateTags() {
+    QSqlQuery query;
+    query.prepare("SELECT artist, album FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook tag query failed: " << query.lastError().databaseText();
+        return;
+    }
+
+    // the first file that has the tag wins
+    QVariant title(QVariant::String);
+    QVariant author(QVariant::String);
+    while(query.next()) {
+        if(title.isNull() && !query.value("album").isNull()) {
+            title = query.value("album");
+        }
+        if(author.isNull() && !query.value("artist").isNull()) {
+            author = query.value("artist");
+        }
+    }
+
+    QSqlQuery updateQuery;
+    updateQuery.prepare("UPDATE audiobooks SET title = ?, author = ? WHERE id = ?");
+    updateQuery.addBindValue(title);
+    updateQuery.addBindValue(author);
+    updateQuery.addBindValue(this->id.toInt());
+
+    if(!updateQuery.exec()) {
+        qWarning() << "audiobook tag save query failed: " << updateQuery.lastError().databaseText();
+    }
 }
 
 bool AudiobookProxy::hasDuration() {
@@ -203,6 +260,17 @@ bool AudiobookProxy::allFileDurationScanned() {
     return scanned;
 }
 
+bool AudiobookProxy::allFileMetadataScanned() {
+    auto fileList = this->getFilesForAudiobook();
+    for(auto &audiobookFile : fileList) {
+        if(!audiobookFile->isMetadataScanned()) {
+            return false;
+        }
+    }
+
+    return true;
+}
+
 std::vector<std::shared_ptr<AudiobookFileProxy>> AudiobookProxy::filesForAudiobookByDb(
         QString audiobookId,
         std::function<std::shared_ptr<AudiobookFileProxy> (QSqlRecord record)> retrieveFileProxyFunction) {
@@ -241,7 +309,7 @@ void AudiobookProxy::updateTotalDuration() {
     std::for_each(funcFileList.begin(), funcFileList.end(),
                   [durationList](std::shared_ptr<AudiobookFileProxy> currentFile) -> long long {
                       auto currentDuration = currentFile->getMediaDuration();
-                      if(currentDuration > 0) {
+                      if(currentDuration > 0 && !currentFile->isShort()) {
                           durationList->push_back(currentDuration);
                       }
 
@@ -260,24 +328,166 @@ void AudiobookProxy::updateCompletionStatus() {
 
     for(int i = 0; i < funcFileList.size(); i++) {
         auto currentFile = funcFileList[i];
+        if(currentFile->isShort()) {
+            continue;
+        }
         totalProgress += currentFile->getCurrentTime();
     }
 
-    double completeness = static_cast<double>(totalProgress) / static_cast<double>(this->getDuration());
-    int percentage = (int)round(completeness * 100);
+    // nothing can be computed until the durations have been scanned
+    int percentage = 0;
+    if(this->getDuration() > 0) {
+        double completeness = static_cast<double>(totalProgress) / static_cast<double>(this->getDuration());
+        percentage = std::min(100, (int)round(completeness * 100));
+    }
 
+    auto previousPercentage = this->getCompleteness();
     this->currentFileSetting->setValue("completeness", percentage);
+    this->syncCompletenessColumn();
+
+    if(previousPercentage < 100 && percentage >= 100) {
+        // an imported finish time is set before the durations are scanned, keep it
+        if(!this->hasFinishedAt()) {
+            this->setFinishedAt(QDateTime::currentDateTimeUtc());
+        }
+        this->notifyCallbacks(AudiobookEvent::Completed);
+    } else if(previousPercentage >= 100 && percentage < 100) {
+        this->setFinishedAt(QDateTime());
+    }
+}
+
+bool AudiobookProxy::hasFinishedAt() {
+    QSqlQuery query;
+    query.prepare("SELECT finished_at FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+    if(!query.exec() || !query.next()) {
+        qWarning() << "audiobook finished_at query failed: " << query.lastError().databaseText();
+        return false;
+    }
+
+    return !query.value("finished_at").isNull();
+}
+
+void AudiobookProxy::setFinishedAt(QDateTime finishedAt) {
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET finished_at = ? WHERE id = ?");
+    query.addBindValue(finishedAt.isValid() ? QVariant(finishedAt) : QVariant(QVariant::String));
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook finished_at save query failed: " << query.lastError().databaseText();
+    }
+}
+
+void AudiobookProxy::syncCompletenessColumn() {
+    auto percentage = this->getCompleteness();
+    if(this->record.value("completeness").toInt() == percentage) {
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET completeness = ? WHERE id = ?");
+    query.addBindValue(percentage);
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook completeness save query failed: " << query.lastError().databaseText();
+        return;
+    }
+
+    this->record.setValue("completeness", percentage);
 }
 
 int AudiobookProxy::getCompleteness() {
     return this->currentFileSetting->value("completeness").toInt();
 }
 
+QString AudiobookProxy::getSpeed() {
+    QSqlQuery query;
+    query.prepare("SELECT playback_speed FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next() && !query.value("playback_speed").isNull()) {
+        return query.value("playback_speed").toString();
+    }
+
+    return this->settings->getSpeed();
+}
+
+void AudiobookProxy::setSpeed(QString speed) {
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET playback_speed = ? WHERE id = ?");
+    query.addBindValue(speed);
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook speed save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+int AudiobookProxy::getVolume() {
+    QSqlQuery query;
+    query.prepare("SELECT playback_volume FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next() && !query.value("playback_volume").isNull()) {
+        return query.value("playback_volume").toInt();
+    }
+
+    return -1;
+}
+
+void AudiobookProxy::setVolume(int volume) {
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET playback_volume = ? WHERE id = ?");
+    query.addBindValue(volume);
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook volume save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+AudiobookResumeMode AudiobookProxy::getResumeMode() {
+    QSqlQuery query;
+    query.prepare("SELECT resume_mode FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next() && query.value("resume_mode").toInt() == ResumeStartOfFile) {
+        return ResumeStartOfFile;
+    }
+
+    return ResumeExactPosition;
+}
+
+void AudiobookProxy::setResumeMode(AudiobookResumeMode resumeMode) {
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET resume_mode = ? WHERE id = ?");
+    query.addBindValue(static_cast<int>(resumeMode));
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook resume mode save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
 void AudiobookProxy::resetReadStatus() {
     for(auto &fileProxy: this->getFilesForAudiobook()) {
         fileProxy->resetReadStatus();
     }
 
+    // start over from the first file next time the audiobook is opened
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET selected_file = NULL WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook selected file reset failed: " << query.lastError().databaseText();
+    }
+
     this->updateCompletionStatus();
 }
 
@@ -289,6 +499,92 @@ void AudiobookProxy::markAsRead() {
     this->updateCompletionStatus();
 }
 
+std::vector<std::shared_ptr<AudiobookFileProxy>> AudiobookProxy::getMissingFiles() {
+    std::vector<std::shared_ptr<AudiobookFileProxy>> missingFiles;
+    for(auto &fileProxy : this->getFilesForAudiobook()) {
+        if(!fileProxy->fileExists()) {
+            missingFiles.push_back(fileProxy);
+        }
+    }
+
+    return missingFiles;
+}
+
+int AudiobookProxy::removeMissingFiles() {
+    auto missingFiles = this->getMissingFiles();
+    if(missingFiles.empty()) {
+        return 0;
+    }
+
+    for(auto &fileProxy : missingFiles) {
+        fileProxy->remove();
+    }
+
+    // the cached list still holds the removed files
+    this->mutex.lock();
+    this->fileListCache.clear();
+    this->mutex.unlock();
+
+    // don't leave the audiobook pointing at a file that is gone
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET selected_file = NULL WHERE id = ? AND "
+                  "selected_file NOT IN (SELECT full_path FROM audiobook_file WHERE audiobook_id = ?)");
+    query.addBindValue(this->id.toInt());
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook selected file reset query failed: " << query.lastError().databaseText();
+    }
+
+    // close the gaps in the positions, the next file is looked up by position
+    this->insertFiles(std::vector<QString>());
+    this->updateTotalDuration();
+    this->updateCompletionStatus();
+
+    return static_cast<int>(missingFiles.size());
+}
+
+int AudiobookProxy::mergeFrom(std::shared_ptr<AudiobookProxy> source) {
+    if(source == nullptr || source.get() == this) {
+        return 0;
+    }
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(source->getId());
+    if(!query.exec()) {
+        qWarning() << "audiobook file list query failed: " << query.lastError().databaseText();
+        return 0;
+    }
+
+    // the files of source keep their order and go after the files of this audiobook
+    int position = static_cast<int>(this->filePathsByPosition().size());
+    int movedCount = 0;
+    while(query.next()) {
+        position++;
+        auto fileProxy = this->retrieveFileProxyFunction(query.record());
+        fileProxy->moveToAudiobook(this->id.toInt(), position);
+        movedCount++;
+    }
+
+    // source has no files left, so this only removes the audiobook itself
+    source->mutex.lock();
+    source->fileListCache.clear();
+    source->mutex.unlock();
+    source->remove();
+
+    this->mutex.lock();
+    this->fileListCache.clear();
+    this->mutex.unlock();
+
+    // a rescan would sort the merged files in between the files of this audiobook otherwise
+    this->setManualOrder(true);
+    this->updateNumberingGaps(this->filePathsByPosition());
+    this->updateTotalDuration();
+    this->updateCompletionStatus();
+
+    return movedCount;
+}
+
 void AudiobookProxy::insertFiles(std::vector<QString> filePathList) {
     auto fileList = this->getFilesForAudiobook();
     std::vector<QString> currentFilePaths;
@@ -296,8 +592,22 @@ void AudiobookProxy::insertFiles(std::vector<QString> filePathList) {
         currentFilePaths.push_back(f->path());
     });
 
-    currentFilePaths.insert(currentFilePaths.end(), filePathList.begin(), filePathList.end());
-    std::sort(currentFilePaths.begin(), currentFilePaths.end());
+    if(this->hasManualOrder()) {
+        // keep the files where the user put them, new files go after them
+        currentFilePaths = this->filePathsByPosition();
+
+        std::vector<QString> newFilePaths;
+        for(auto &path : filePathList) {
+            if(std::find(currentFilePaths.begin(), currentFilePaths.end(), path) == currentFilePaths.end()) {
+                newFilePaths.push_back(path);
+            }
+        }
+        this->sortFilePaths(newFilePaths);
+        currentFilePaths.insert(currentFilePaths.end(), newFilePaths.begin(), newFilePaths.end());
+    } else {
+        currentFilePaths.insert(currentFilePaths.end(), filePathList.begin(), filePathList.end());
+        this->sortFilePaths(currentFilePaths);
+    }
 
     for(int i = 0; i < currentFilePaths.size(); i++) {
         int position = i + 1;
@@ -319,18 +629,134 @@ void AudiobookProxy::insertFiles(std::vector<QString> filePathList) {
 
         } else {
             // otherwise, we need to update the position
-            QString queryString = "UPDATE audiobook_file SET position = ? WHERE full_path = ? AND audiobook_id = ?";
-            QSqlQuery queryObject;
-            queryObject.prepare(queryString);
-            queryObject.addBindValue(position);
-            queryObject.addBindValue(currentPath);
-            queryObject.addBindValue(this->id);
-            queryObject.exec();
+            fileObject->setPosition(position);
         }
     }
 
     // force this object to update the audiobook listing
     this->getFilesForAudiobook(true);
+
+    this->updateNumberingGaps(currentFilePaths);
+}
+
+std::vector<QString> AudiobookProxy::filePathsByPosition() {
+    std::vector<QString> filePaths;
+
+    QSqlQuery query;
+    query.prepare("SELECT full_path FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook file order query failed: " << query.lastError().databaseText();
+        return filePaths;
+    }
+
+    while(query.next()) {
+        filePaths.push_back(query.value("full_path").toString());
+    }
+
+    return filePaths;
+}
+
+bool AudiobookProxy::hasManualOrder() {
+    QSqlQuery query;
+    query.prepare("SELECT manual_order FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    return query.exec() && query.next() && query.value("manual_order").toBool();
+}
+
+void AudiobookProxy::setManualOrder(bool manualOrder) {
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET manual_order = ? WHERE id = ?");
+    query.addBindValue(manualOrder ? 1 : 0);
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook manual order save query failed: " << query.lastError().databaseText();
+    }
+}
+
+bool AudiobookProxy::moveFile(int from, int to) {
+    auto filePaths = this->filePathsByPosition();
+    int fileCount = static_cast<int>(filePaths.size());
+    if(from < 0 || from >= fileCount || to < 0 || to >= fileCount || from == to) {
+        return false;
+    }
+
+    auto movedPath = filePaths[from];
+    filePaths.erase(filePaths.begin() + from);
+    filePaths.insert(filePaths.begin() + to, movedPath);
+
+    for(int i = 0; i < fileCount; i++) {
+        auto fileObject = this->getFileForPath(filePaths[i]);
+        if(fileObject != nullptr) {
+            fileObject->setPosition(i + 1);
+        }
+    }
+
+    this->setManualOrder(true);
+    return true;
+}
+
+void AudiobookProxy::resetFileOrder() {
+    this->setManualOrder(false);
+
+    // sorts the files that are already there again
+    this->insertFiles(std::vector<QString>());
+}
+
+int AudiobookProxy::automaticFileSortMode() {
+    QSqlQuery query;
+    query.prepare("SELECT file_sort FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next() && !query.value("file_sort").isNull()) {
+        return query.value("file_sort").toInt();
+    }
+
+    return this->settings->getFileSortMode();
+}
+
+int AudiobookProxy::getFileSortMode() {
+    if(this->hasManualOrder()) {
+        return Core::FileSortManual;
+    }
+
+    return this->automaticFileSortMode();
+}
+
+void AudiobookProxy::setFileSortMode(int fileSortMode) {
+    if(fileSortMode == Core::FileSortManual) {
+        // the current order is kept, it only changes once the user moves a file
+        this->setManualOrder(true);
+        return;
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET file_sort = ? WHERE id = ?");
+    query.addBindValue(fileSortMode);
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        qWarning() << "audiobook file sort save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+
+    this->resetFileOrder();
+}
+
+void AudiobookProxy::updateNumberingGaps(const std::vector<QString>& filePaths) {
+    QStringList fileNames;
+    for(auto &filePath : filePaths) {
+        fileNames << QFileInfo(filePath).fileName();
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET has_gaps = ? WHERE id = ?");
+    query.addBindValue(Core::hasNumberingGaps(fileNames) ? 1 : 0);
+    query.addBindValue(this->id);
+    if(!query.exec()) {
+        qWarning() << "audiobook has_gaps save query failed: " << query.lastError().databaseText();
+    }
 }
 
 std::shared_ptr<AudiobookFileProxy> AudiobookProxy::getFileForPath(QString path) {
@@ -347,4 +773,236 @@ std::shared_ptr<AudiobookFileProxy> AudiobookProxy::getFileForPath(QString path)
     return fileContainer;
 }
 
+void AudiobookProxy::sortFilePaths(std::vector<QString>& filePaths) {
+    std::sort(filePaths.begin(), filePaths.end(), Core::naturalLessThan);
+
+    auto fileSortMode = this->automaticFileSortMode();
+    if(fileSortMode == Core::FileSortModified) {
+        // files modified at the same time keep the order of their names
+        std::stable_sort(filePaths.begin(), filePaths.end(), [](const QString& first, const QString& second) {
+            auto directoryOrder = Core::compareParentDirectory(first, second);
+            if(directoryOrder != 0) {
+                return directoryOrder < 0;
+            }
+            return Core::modifiedEarlier(first, second);
+        });
+        return;
+    }
+
+    if(fileSortMode != Core::FileSortTrackTag) {
+        return;
+    }
+
+    // only use the track numbers when every file has one, a partially tagged
+    // audiobook would end up in a confusing order otherwise
+    QHash<QString, int> trackNumbers;
+    QSqlQuery query;
+    query.prepare("SELECT full_path, track_number FROM audiobook_file WHERE audiobook_id = ?");
+    query.addBindValue(this->id.toInt());
+    if(!query.exec()) {
+        return;
+    }
+
+    while(query.next()) {
+        if(!query.value("track_number").isNull()) {
+            trackNumbers.insert(query.value("full_path").toString(), query.value("track_number").toInt());
+        }
+    }
+
+    for(auto &path : filePaths) {
+        if(!trackNumbers.contains(path)) {
+            return;
+        }
+    }
+
+    // discs of a combined audiobook usually each start at track 1, so the disc comes first
+    std::stable_sort(filePaths.begin(), filePaths.end(), [&trackNumbers](const QString& first, const QString& second) {
+        auto directoryOrder = Core::compareParentDirectory(first, second);
+        if(directoryOrder != 0) {
+            return directoryOrder < 0;
+        }
+        return trackNumbers.value(first) < trackNumbers.value(second);
+    });
+}
+
+QStringList AudiobookProxy::findCoverSources() {
+    QStringList sources;
+
+    auto coverOverride = this->getCoverOverride();
+    if(!coverOverride.isEmpty() && QFileInfo(coverOverride).isFile()) {
+        sources << coverOverride;
+    }
+
+    for(auto &fileProxy : this->getFilesForAudiobook()) {
+        auto artworkPath = fileProxy->getArtworkPath();
+        if(!artworkPath.isEmpty() && QFileInfo(artworkPath).isFile() && !sources.contains(artworkPath)) {
+            sources << artworkPath;
+        }
+    }
+
+    QDir dir(this->directory);
+    for(auto coverName : FOLDER_COVER_NAMES) {
+        if(dir.exists(coverName)) {
+            sources << dir.filePath(coverName);
+        }
+    }
+
+    return sources;
+}
+
+void AudiobookProxy::updateCover(bool forced) {
+    if(this->isNull) {
+        return;
+    }
+
+    if(!forced && this->currentFileSetting->value("coverChecked", false).toBool()) {
+        return;
+    }
+
+    this->currentFileSetting->setValue("coverChecked", true);
+
+    auto coverPath = this->coverCachePath(this->id + ".png");
+
+    // a damaged image is skipped for the next candidate, with none left the list shows no cover
+    for(auto &source : this->findCoverSources()) {
+        QString errorMessage;
+        if(Core::createCoverThumbnail(source, coverPath, COVER_THUMBNAIL_SIZE, &errorMessage)) {
+            this->currentFileSetting->setValue("coverPath", coverPath);
+            return;
+        }
+
+        qWarning() << "Cover thumbnail from " << source << " failed: " << errorMessage;
+    }
+
+    this->currentFileSetting->remove("coverPath");
+}
+
+QString AudiobookProxy::coverCachePath(QString fileName) {
+    auto coverDirectory = QDir(Core::getSettingPath() + "/covers").absolutePath();
+    Core::createPathIfNotExists(coverDirectory);
+
+    return QDir(coverDirectory).filePath(fileName);
+}
+
+QString AudiobookProxy::getDisplayName() {
+    QSqlQuery query;
+    query.prepare("SELECT display_name FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next()) {
+        return query.value("display_name").toString();
+    }
+
+    return QString();
+}
+
+void AudiobookProxy::setDisplayName(QString displayName) {
+    QVariant storedName(QVariant::String);
+    if(!displayName.trimmed().isEmpty()) {
+        storedName = displayName.trimmed();
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET display_name = ? WHERE id = ?");
+    query.addBindValue(storedName);
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook display name save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+    }
+}
+
+QString AudiobookProxy::getCoverOverride() {
+    QSqlQuery query;
+    query.prepare("SELECT cover_override_path FROM audiobooks WHERE id = ?");
+    query.addBindValue(this->id.toInt());
+
+    if(query.exec() && query.next()) {
+        return query.value("cover_override_path").toString();
+    }
+
+    return QString();
+}
+
+bool AudiobookProxy::setCoverOverride(QString imagePath, QString* errorMessage) {
+    if(this->isNull) {
+        return false;
+    }
+
+    // the picked image is kept in the cover cache, so it still works if the original is moved or deleted
+    auto overridePath = this->coverCachePath(this->id + "-override.png");
+    QVariant storedPath(QVariant::String);
+    if(imagePath.isEmpty()) {
+        QFile::remove(overridePath);
+    } else {
+        if(!Core::createCoverThumbnail(imagePath, overridePath, COVER_THUMBNAIL_SIZE, errorMessage)) {
+            return false;
+        }
+        storedPath = overridePath;
+    }
+
+    QSqlQuery query;
+    query.prepare("UPDATE audiobooks SET cover_override_path = ? WHERE id = ?");
+    query.addBindValue(storedPath);
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook cover override save query failed: "
+                   << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        if(errorMessage != nullptr) {
+            *errorMessage = query.lastError().databaseText();
+        }
+        return false;
+    }
+
+    this->updateCover(true);
+    return true;
+}
+
+QString AudiobookProxy::getCoverPath() {
+    if(this->isNull) {
+        return QString();
+    }
+
+    return this->currentFileSetting->value("coverPath").toString();
+}
+
+int AudiobookProxy::getId() {
+    return this->id.toInt();
+}
+
+QString AudiobookProxy::getPath() {
+    return this->directory;
+}
+
+std::shared_ptr<AudiobookFileProxy> AudiobookProxy::getFirstUnfinishedFile() {
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobook_file WHERE audiobook_id = ? ORDER BY position ASC");
+    query.addBindValue(this->id.toInt());
+
+    if(!query.exec()) {
+        qWarning() << "audiobook file list query failed: " << query.lastError().databaseText();
+        return nullptr;
+    }
+
+    std::shared_ptr<AudiobookFileProxy> firstFile = nullptr;
+    while(query.next()) {
+        auto fileProxy = this->retrieveFileProxyFunction(query.record());
+        if(!fileProxy->fileExists()) {
+            continue;
+        }
+
+        if(firstFile == nullptr) {
+            firstFile = fileProxy;
+        }
 
+        if(fileProxy->getCompleteness() < 100) {
+            return fileProxy;
+        }
+    }
+
+    return firstFile;
+}
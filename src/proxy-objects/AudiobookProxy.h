@@ -21,12 +21,22 @@
 
 class ProxyManager;
 
+/**
+ * Where playback picks up when a file of the audiobook is opened again
+ */
+enum AudiobookResumeMode {
+    ResumeExactPosition = 0,
+    ResumeStartOfFile = 1
+};
+
 /**
  * AudiobookEvent are events that can potentially be initiated from this
  */
 enum AudiobookEvent {
     Removed,
-    Added
+    Added,
+    // fired once when the progress reaches 100%, from whichever thread updated the progress
+    Completed
 };
 
 /**
@@ -59,6 +69,38 @@ class AudiobookProxy : public QObject {
 
     void notifyCallbacks(AudiobookEvent event);
 
+    // sort file paths in the order they should be played
+    void sortFilePaths(std::vector<QString>& filePaths);
+
+    // file paths in the order they are stored in, i.e. the current play order
+    std::vector<QString> filePathsByPosition();
+
+    // once the user reordered the files, rescans keep that order instead of sorting again
+    bool hasManualOrder();
+    void setManualOrder(bool manualOrder);
+
+    // the file order of this audiobook, or the default from the settings, ignoring a manual order
+    int automaticFileSortMode();
+
+    // the completeness column is only a copy of the progress, used to sort the audiobook list
+    void syncCompletenessColumn();
+
+    // an invalid date clears it
+    void setFinishedAt(QDateTime finishedAt);
+    bool hasFinishedAt();
+
+    // flag the audiobook when the numbering of its files skips a number, which usually means a file is missing
+    void updateNumberingGaps(const std::vector<QString>& filePaths);
+
+    // fill in the audiobook title and author from the tags of its files
+    void updateTags();
+
+    // images that could be used as the cover of this audiobook, the preferred one first
+    QStringList findCoverSources();
+
+    // path of fileName in the cover cache, the cache directory is created if needed
+    QString coverCachePath(QString fileName);
+
     // internal function to load AudiobookFileProxy objects from database
     std::vector<std::shared_ptr<AudiobookFileProxy>> filesForAudiobookByDb(QString audiobookId,
                                                                            std::function<std::shared_ptr<AudiobookFileProxy>(QSqlRecord)>
@@ -69,6 +111,8 @@ public:
                    Core::Setting* settings,
                    std::function<std::shared_ptr<AudiobookFileProxy> (QSqlRecord record)> retrieveFileProxyFunction);
     QAction* getRemoveAction();
+    int getId();
+    QString getPath();
     std::vector<std::shared_ptr<AudiobookFileProxy>> getFilesForAudiobook(bool forced = true);
 
     /**
@@ -83,20 +127,68 @@ public:
 
     bool hasDuration();
     bool allFileDurationScanned();
+    bool allFileMetadataScanned();
     long long getDuration();
     void setDuration(const long long duration);
     void insertFiles(std::vector<QString> vector);
     std::shared_ptr<AudiobookFileProxy> getFileForPath(QString path);
 
+    // move the file at index from to index to in the play order, both start at 0
+    bool moveFile(int from, int to);
+
+    // drop the order set by the user and sort the files again
+    void resetFileOrder();
+
+    // one of Core::FileSortMode, a file sort mode other than manual sorts the files again right away
+    int getFileSortMode();
+    void setFileSortMode(int fileSortMode);
+
+    // the first existing file that hasn't been completed, or the first file if everything was completed
+    std::shared_ptr<AudiobookFileProxy> getFirstUnfinishedFile();
+
 
     int getCompleteness();
+
+    // cover thumbnail, generated from the embedded art or a cover image in the folder
+    void updateCover(bool forced = false);
+    QString getCoverPath();
+
+    // a cover picked by the user, it is used before any embedded or folder art
+    QString getCoverOverride();
+    // an empty imagePath goes back to the automatic cover, false with the reason in errorMessage
+    // if the image can't be used
+    bool setCoverOverride(QString imagePath, QString* errorMessage = nullptr);
+
+    // name picked by the user, shown instead of the folder name and tag title, empty if there is none
+    QString getDisplayName();
+    // an empty name goes back to the folder name or tag title
+    void setDisplayName(QString displayName);
+
+    // playback speed remembered for this audiobook, falls back to the global speed
+    QString getSpeed();
+    void setSpeed(QString speed);
+
+    // volume remembered for this audiobook, -1 if it was never changed while the audiobook was selected
+    int getVolume();
+    void setVolume(int volume);
+
+    AudiobookResumeMode getResumeMode();
+    void setResumeMode(AudiobookResumeMode resumeMode);
+
     void handlePropertyScanFinished();
     void updateTotalDuration();
     void updateCompletionStatus();
 
+    // files that are on record but can no longer be found on disk
+    std::vector<std::shared_ptr<AudiobookFileProxy>> getMissingFiles();
+
     // direct user actions
     void resetReadStatus();
     void markAsRead();
+    int removeMissingFiles();
+    // move the files of source to the end of this audiobook and remove source, returns how many files were moved
+    // the files stay where they are on disk
+    int mergeFrom(std::shared_ptr<AudiobookProxy> source);
 
 public slots:
     void remove();
@@ -48,6 +48,15 @@ std::shared_ptr<AudiobookFileProxy> ProxyManager::getAudiobookFileProxy(QSqlReco
     }
 }
 
+QStringList ProxyManager::getKnownPaths() {
+    QStringList paths = this->abFileCache.keys();
+    for(auto &audiobook : this->loadedAudiobooks) {
+        paths << audiobook->getPath();
+    }
+
+    return paths;
+}
+
 void ProxyManager::clearCache() {
     this->abFileCache.clear();
 }
@@ -33,6 +33,8 @@ public:
     void clearCache();
     std::shared_ptr<AudiobookFileProxy> getAudiobookFileProxy(QSqlRecord);
     std::shared_ptr<AudiobookProxy> getAudiobookProxy(QSqlRecord);
+    // paths of the audiobooks and files that have been loaded
+    QStringList getKnownPaths();
     ProxyManager(Core::Setting* settings);
 };
 
@@ -5,10 +5,36 @@
 #include <src/model/AudiobookListDelegate.h>
 #include <src/proxy-objects/ProxyManager.h>
 #include <QFileDialog>
+#include <QFile>
+#include <QFileInfo>
+#include <QDateTime>
+#include <algorithm>
+#include <QSignalBlocker>
+#include <QMessageBox>
+#include <QToolTip>
+#include <QShortcut>
+#include <QClipboard>
+#include <QApplication>
+#include <QDir>
+#include <QSqlDatabase>
+#include <QSysInfo>
+#include <QTextStream>
+#include <src/core/ListeningStats.h>
+#include <src/core/DuplicateScan.h>
+#include <src/core/LibraryExport.h>
+#include <src/core/ProgressImport.h>
+#include <src/core/Collections.h>
+#include <src/core/LogBuffer.h>
 #include "MainWindow.h"
 
 const static int MAXIMUM_VOLUME = 150;
 
+// how many unreadable files are named after a scan
+const static int MAX_LISTED_PROBLEM_FILES = 3;
+
+// buffering that is over quicker than this doesn't show up on the play button
+const static int LOADING_INDICATOR_DELAY_MS = 250;
+
 MainWindow::MainWindow(Directory* directoryModel,
                        Audiobook* audiobookModel,
                        Core::ConcretePlayer* player,
@@ -22,6 +48,7 @@ MainWindow::MainWindow(Directory* directoryModel,
     this->setIsPlaying(false);
 
     this->settings = setting;
+    this->proxyManager = manager;
 
     // we will need this reference so FileList can make direct reference to it
     this->concretePlayer = player;
@@ -36,6 +63,7 @@ MainWindow::MainWindow(Directory* directoryModel,
                                                        this->ui->audiobookView,
                                                        this->ui->fileView,
                                                        manager,
+                                                       this->settings,
                                                        this);
     this->fileListHandler = new FileListViewHandler(this,
                                                     this->ui->fileView,
@@ -45,11 +73,19 @@ MainWindow::MainWindow(Directory* directoryModel,
 
     // set up a null file
     this->currentlyPlayingFile = std::shared_ptr<AudiobookFileProxy>(new AudiobookFileProxy());
+    this->isSelectingAudiobook = false;
 
-    this->fileDisplayModel = new FileDisplayModel(manager, this);
+    this->loadingTimer = new QTimer(this);
+    this->loadingTimer->setSingleShot(true);
+    this->loadingTimer->setInterval(LOADING_INDICATOR_DELAY_MS);
+    connect(this->loadingTimer, &QTimer::timeout, [this]() {
+        this->setIsLoading(true);
+    });
+
+    this->fileDisplayModel = new FileDisplayModel(manager, this->settings, this);
 
     // initialize the settings form
-    this->settingsForm = new SettingsForm(this->directoryModel);
+    this->settingsForm = new SettingsForm(this->directoryModel, this->settings);
     this->settingsForm->hide();
 
 
@@ -62,17 +98,48 @@ void MainWindow::menuSetup() {
     QAction* settings = new QAction("Add/Remove Scanning Directories");
     QAction* audiobookAdd = new QAction("Add an Audiobook");
     QAction* rescan = new QAction("Rescan Directories");
+    QAction* clearQueue = new QAction("Clear Queue");
+    QAction* exportStats = new QAction("Export Listening Statistics");
+    QAction* exportLibrary = new QAction("Export Library");
+    QAction* importLibrary = new QAction("Import Library");
+    QAction* importProgress = new QAction("Import Progress from CSV");
+    QAction* removeMissingFiles = new QAction("Remove Missing Files");
+    QAction* findDuplicates = new QAction("Find Duplicate Audiobooks");
+    QAction* copyTimestamp = new QAction("Copy Current Timestamp");
+    QAction* diagnostics = new QAction("Diagnostics");
+    copyTimestamp->setShortcut(QKeySequence("Ctrl+Shift+C"));
 
     // connect the actions to specific functions that will trigger its functionality
     connect(settings, &QAction::triggered, this, &MainWindow::performSettings);
     connect(rescan, &QAction::triggered, this, &MainWindow::performRescan);
     connect(audiobookAdd, &QAction::triggered, this, &MainWindow::performAudiobookAdd);
+    connect(clearQueue, &QAction::triggered, this->settings, &Core::Setting::clearQueue);
+    connect(exportStats, &QAction::triggered, this, &MainWindow::performExportStats);
+    connect(exportLibrary, &QAction::triggered, this, &MainWindow::performExportLibrary);
+    connect(importLibrary, &QAction::triggered, this, &MainWindow::performImportLibrary);
+    connect(importProgress, &QAction::triggered, this, &MainWindow::performImportProgress);
+    connect(removeMissingFiles, &QAction::triggered, this, &MainWindow::performRemoveMissingFiles);
+    connect(findDuplicates, &QAction::triggered, this, &MainWindow::performFindDuplicates);
+    connect(copyTimestamp, &QAction::triggered, this, &MainWindow::performCopyTimestamp);
+    connect(diagnostics, &QAction::triggered, this, &MainWindow::performShowDiagnostics);
+
+    // the shortcut has to work without opening the menu
+    this->addAction(copyTimestamp);
 
     // create the menu and add all of the actions
     this->audiobookMenu = new QMenu("Audiobook Menu", this->ui->abToolButton);
     this->audiobookMenu->addAction(audiobookAdd);
     this->audiobookMenu->addAction(rescan);
+    this->audiobookMenu->addAction(removeMissingFiles);
+    this->audiobookMenu->addAction(findDuplicates);
+    this->audiobookMenu->addAction(clearQueue);
+    this->audiobookMenu->addAction(copyTimestamp);
+    this->audiobookMenu->addAction(exportStats);
+    this->audiobookMenu->addAction(exportLibrary);
+    this->audiobookMenu->addAction(importLibrary);
+    this->audiobookMenu->addAction(importProgress);
     this->audiobookMenu->addAction(settings);
+    this->audiobookMenu->addAction(diagnostics);
 
     // connect the menu
     connect(this->ui->abToolButton, &QToolButton::clicked, [this]() {
@@ -102,10 +169,29 @@ void MainWindow::setup() {
     this->ui->currentlyPlayingLabel->setTextFormat(Qt::RichText);
     this->setLabel(this->ui->currentlyPlayingLabel);
 
+    // the time in the label is a link that switches between elapsed and remaining time
+    connect(this->ui->currentlyPlayingLabel, &QLabel::linkActivated, this, &MainWindow::performToggleTimeDisplay);
+
 
     // populate the speed combo box
     this->populateSpeedChoose();
 
+    // populate the audiobook sort combo box
+    this->populateSortChooser();
+
+    // collections are edited in the settings, the chooser follows along
+    this->populateCollectionChooser();
+    connect(this->directoryModel, &Directory::collectionsChanged, this, &MainWindow::populateCollectionChooser);
+    connect(this->ui->collectionChooser,
+            static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
+            [this](int index) -> void {
+        if(index == -1) {
+            return;
+        }
+
+        this->audiobookModel->setCollectionFilter(this->ui->collectionChooser->itemData(index).toInt());
+    });
+
 
     // set up the audobook view
     auto audiobookListDelegate = new AudiobookListDelegate(AB_ITEM_STYLESHEET, 20);
@@ -115,12 +201,35 @@ void MainWindow::setup() {
     this->ui->audiobookView->setFocusPolicy(Qt::NoFocus);
     this->ui->audiobookView->setSelectionMode(QAbstractItemView::SingleSelection);
 
+    // the continue button is only useful once something has been played
+    this->ui->continueButton->setEnabled(this->settings->getCurrentAudiobookId() != -1);
+    connect(this->ui->continueButton, &QToolButton::clicked, this, &MainWindow::performContinue);
+
+    // filter the audiobook list as the user types, the search from last time is filled in first
+    this->ui->searchEdit->setText(this->audiobookModel->getSearchQuery());
+    connect(this->ui->searchEdit, &QLineEdit::textChanged, [this](const QString &text) {
+        this->audiobookModel->setSearchQuery(text);
+    });
+
+    // an empty list because of the filters shouldn't look like an empty library
+    this->ui->noMatchesLabel->setTextFormat(Qt::RichText);
+    this->ui->noMatchesLabel->setText("No audiobooks match the search or collection. "
+                                      "<a href=\"show-all\">Show all audiobooks</a>");
+    connect(this->ui->noMatchesLabel, &QLabel::linkActivated, [this]() {
+        this->ui->searchEdit->clear();
+        this->ui->collectionChooser->setCurrentIndex(0);
+    });
+    connect(this->audiobookModel, &QAbstractItemModel::modelReset, this, &MainWindow::updateNoMatchesLabel);
+    this->updateNoMatchesLabel();
+
 
     // connect the audiobook view events to the file selector view
     auto audiobookModel = this->audiobookModel;
     connect(this->ui->audiobookView->selectionModel(), &QItemSelectionModel::selectionChanged,
             [this, audiobookModel] (const QItemSelection &selected, const QItemSelection &deselected) {
                 if(selected.indexes().size() > 0) {
+                    this->setLibraryStatsVisible(false);
+
                     // set the audiobook file list
                     auto modelIndex = selected.indexes().first();
                     auto record = audiobookModel->record(modelIndex.row());
@@ -128,7 +237,10 @@ void MainWindow::setup() {
 
                     this->fileDisplayModel->setSelectedAudiobook(audiobookId);
 
-                    // set the selected audiobook file if it exists
+                    this->selectedAudiobook = this->proxyManager->getAudiobookProxy(record);
+
+                    // restore the file this audiobook was left off at, the model may not have the latest
+                    // selected_file since the player writes it directly
                     QSqlQuery query;
                     query.prepare("SELECT id, selected_file from audiobooks WHERE id=?");
                     query.addBindValue(audiobookId);
@@ -138,31 +250,67 @@ void MainWindow::setup() {
                         qWarning() << "Something went wrong: "
                                    << error.driverText()
                                    << ", " << error.databaseText();
-                    } else {
-                        if(query.next()) {
-                            // if we are here, it means the current file exists, and we are setting the selection
-                            auto currentRecord = query.record();
-                            QString path = "";
-                            if(!currentRecord.value("selected_file").isNull()) {
-                                path = currentRecord.value("selected_file").toString();
-                                this->setSelectedFile(path);
-                            } else {
-                                // don't even proceed if this failed
-                                return;
-                            }
+                        return;
+                    }
 
-                            // check if the player does not have a media loaded, if the player does not have a media loaded
-                            // it means the player hasn't loaded a file yet, we should load whichever file the user left off
-                            // if that is the case...
-                            if(this->concretePlayer->getAudiobookFile() == nullptr) {
-                                auto index = this->fileDisplayModel->getFileIndex(path);
-                                auto currentFileRecord = this->fileDisplayModel->record(index.row());
+                    std::shared_ptr<AudiobookFileProxy> fileToSelect = nullptr;
+                    if(query.next() && !query.record().value("selected_file").isNull()) {
+                        auto storedFile = this->selectedAudiobook->getFileForPath(
+                                query.record().value("selected_file").toString());
+                        if(storedFile != nullptr && storedFile->fileExists()) {
+                            fileToSelect = storedFile;
+                        }
+                    }
+
+                    // never played, or the file it was left off at is gone
+                    if(fileToSelect == nullptr) {
+                        fileToSelect = this->selectedAudiobook->getFirstUnfinishedFile();
+                    }
+                    if(fileToSelect == nullptr) {
+                        return;
+                    }
 
-                                this->concretePlayer->loadMedia(currentFileRecord);
+                    this->setSelectedFile(fileToSelect->path());
+
+                    // switch the player over unless it's playing, the saved position is restored once the file is parsed
+                    auto loadedFile = this->concretePlayer->getAudiobookFile();
+                    bool isLoaded = loadedFile != nullptr && loadedFile->path() == fileToSelect->path();
+                    bool isPlaying = this->concretePlayer->getCurrentState() == libvlc_Playing;
+                    if(!isLoaded && !isPlaying && this->concretePlayer->canLoadMedia(fileToSelect->getRecord())) {
+                        // restore the playback speed remembered for this audiobook
+                        auto audiobookSpeed = this->selectedAudiobook->getSpeed();
+                        {
+                            // the speed is already stored, we don't want the chooser to write it back
+                            QSignalBlocker blocker(this->ui->speedChooser);
+                            this->setSpeed(audiobookSpeed);
+                        }
+                        this->concretePlayer->setSpeed(audiobookSpeed);
+
+                        // restore the volume remembered for this audiobook, otherwise a new audiobook starts at
+                        // the default volume and only the player is changed so the global volume stays as it was
+                        auto volume = this->selectedAudiobook->getVolume();
+                        if(volume == -1) {
+                            volume = this->settings->getVolume();
+                            if(this->settings->getDefaultVolume() > 0 && record.value("last_played_at").isNull()) {
+                                volume = this->settings->getDefaultVolume();
                             }
                         }
+                        this->ui->volumeSlider->setValue(volume);
+                        this->concretePlayer->setVolume(volume);
+
+                        this->concretePlayer->releaseMedia();
+                        this->concretePlayer->loadMedia(fileToSelect->getRecord());
+                        isLoaded = true;
+                    }
+
+                    // only a selection made by the user starts playback, not one restored at startup
+                    if(isLoaded && !isPlaying && !this->isSelectingAudiobook && this->settings->getAutoPlayOnSelect()) {
+                        this->concretePlayer->play(true);
                     }
 
+                } else if(!this->ui->audiobookView->selectionModel()->hasSelection()) {
+                    this->selectedAudiobook = nullptr;
+                    this->setLibraryStatsVisible(true);
                 }
             });
 
@@ -180,10 +328,29 @@ void MainWindow::setup() {
                 auto row = index.row();
                 auto currentRecord = model->record(row);
 
+                // the file is already loaded, reloading it would start it over
+                auto loadedFile = this->concretePlayer->getAudiobookFile();
+                if(loadedFile != nullptr && loadedFile->path() == currentRecord.value("full_path").toString() &&
+                   this->concretePlayer->getCurrentState() != libvlc_NothingSpecial &&
+                   this->concretePlayer->getCurrentState() != libvlc_Ended) {
+                    auto reselectBehavior = this->settings->getReselectBehavior();
+                    if(reselectBehavior == Core::ReselectIgnore) {
+                        return;
+                    }
+                    if(reselectBehavior == Core::ReselectTogglePlayPause) {
+                        if(this->isPlaying) {
+                            this->concretePlayer->stop(true);
+                        } else {
+                            this->concretePlayer->resume();
+                        }
+                        return;
+                    }
+                }
+
                 if(this->concretePlayer->canLoadMedia(currentRecord)) {
                     this->concretePlayer->releaseMedia();
                     this->concretePlayer->loadMedia(currentRecord);
-                    this->concretePlayer->play();
+                    this->concretePlayer->play(true);
                 }
     });
 
@@ -198,23 +365,76 @@ void MainWindow::setup() {
     // define what the play button do
     connect(this->ui->playButton, &QPushButton::clicked, [=]() {
         if(this->isPlaying) {
-            this->concretePlayer->stop();
+            this->concretePlayer->stop(true);
         } else {
-            this->concretePlayer->play();
+            this->concretePlayer->resume();
         }
     });
 
+    // set up the A-B repeat controls
+    connect(this->ui->repeatStartButton, &QToolButton::clicked, [=]() {
+        this->concretePlayer->setRepeatStart();
+        this->updateRepeatButtons();
+    });
+
+    connect(this->ui->repeatEndButton, &QToolButton::clicked, [=]() {
+        this->concretePlayer->setRepeatEnd();
+        this->updateRepeatButtons();
+    });
+
+    connect(this->ui->clearRepeatButton, &QToolButton::clicked, [=]() {
+        this->concretePlayer->clearRepeat();
+        this->updateRepeatButtons();
+    });
+
+    // the player clears the loop on its own when the file changes
+    connect(this->concretePlayer, &Core::ConcretePlayer::repeatChanged,
+            this, &MainWindow::updateRepeatButtons);
+
+    // go back to where the last slider drag or jump started from
+    auto undoSeekShortcut = new QShortcut(QKeySequence::Undo, this);
+    connect(undoSeekShortcut, &QShortcut::activated, [=]() {
+        this->concretePlayer->undoSeek();
+    });
+
+    // [ and ] go through the speed presets, with ctrl they change the speed in small steps
+    auto speedDownShortcut = new QShortcut(QKeySequence("["), this);
+    connect(speedDownShortcut, &QShortcut::activated, [=]() {
+        this->performStepSpeed(false, false);
+    });
+    auto speedUpShortcut = new QShortcut(QKeySequence("]"), this);
+    connect(speedUpShortcut, &QShortcut::activated, [=]() {
+        this->performStepSpeed(true, false);
+    });
+    auto fineSpeedDownShortcut = new QShortcut(QKeySequence("Ctrl+["), this);
+    connect(fineSpeedDownShortcut, &QShortcut::activated, [=]() {
+        this->performStepSpeed(false, true);
+    });
+    auto fineSpeedUpShortcut = new QShortcut(QKeySequence("Ctrl+]"), this);
+    connect(fineSpeedUpShortcut, &QShortcut::activated, [=]() {
+        this->performStepSpeed(true, true);
+    });
+
+    // bring the selected audiobook and file back into view after scrolling away
+    connect(this->ui->revealButton, &QToolButton::clicked, this, &MainWindow::performRevealNowPlaying);
+
+    // jump to a typed in time of the current file
+    connect(this->ui->jumpToTimeEdit, &QLineEdit::returnPressed, this, &MainWindow::performJumpToTime);
+
     // connect the changes from the progress slider to the player
     connect(this->ui->progressSlider, &QSlider::sliderMoved,
             concretePlayer, &Core::ConcretePlayer::updateSeekPosition);
 
     connect(this->ui->progressSlider, &QSlider::sliderPressed,
             [=]() {
+                this->concretePlayer->rememberSeekPosition();
+                this->concretePlayer->setSeeking(true);
                 this->concretePlayer->stop();
             });
 
     connect(this->ui->progressSlider, &QSlider::sliderReleased,
             [=]() {
+                this->concretePlayer->setSeeking(false);
                 this->concretePlayer->play();
             });
 
@@ -222,8 +442,45 @@ void MainWindow::setup() {
     this->ui->volumeSlider->setMaximum(MAXIMUM_VOLUME);
     this->ui->volumeSlider->setValue(settings->getVolume());
 
-    connect(this->ui->volumeSlider, &QSlider::sliderMoved,
-            this->settings, &Core::Setting::setVolume);
+    connect(this->ui->volumeSlider, &QSlider::sliderMoved, [this](int volume) {
+        // the volume belongs to the audiobook that is playing, which isn't always the selected one,
+        // and the global volume is used when nothing is loaded
+        auto loadedAudiobook = this->getLoadedAudiobook();
+        if(loadedAudiobook != nullptr) {
+            loadedAudiobook->setVolume(volume);
+            this->concretePlayer->setVolume(volume);
+        } else {
+            this->settings->setVolume(volume);
+        }
+    });
+
+    // nothing is selected yet, so the library overview takes the place of the file list
+    this->setLibraryStatsVisible(true);
+
+    // the scan progress is only shown while files are being scanned
+    this->ui->scanProgressBar->hide();
+    this->ui->rescanLabel->hide();
+    connect(this->scanPlayer, &Core::ScanPlayer::scanProgressUpdated,
+            this, &MainWindow::updateScanProgress);
+    connect(this->abListHandler, &AudiobookListViewHandler::integrityProgressed,
+            this, &MainWindow::updateVerifyProgress);
+
+    // files that turned out to be damaged stay listed, this just says which ones they are
+    this->ui->scanProblemsLabel->hide();
+    connect(this->scanPlayer, &Core::ScanPlayer::problemFilesFound,
+            this, &MainWindow::showScanProblems);
+    connect(this->ui->scanProblemsLabel, &QLabel::linkActivated, [this]() {
+        this->ui->scanProblemsLabel->hide();
+    });
+
+    // show how many audiobooks are waiting in the queue
+    this->updateQueueLabel(this->settings->getQueue().size());
+    connect(this->settings, &Core::Setting::queueUpdated,
+            this, &MainWindow::updateQueueLabel);
+
+    // follow the player when it moves on to the next queued audiobook
+    connect(this->concretePlayer, &Core::ConcretePlayer::audiobookChanged,
+            this, &MainWindow::selectAudiobook);
 
     loadCurrentAudiobookIfExists();
 }
@@ -254,15 +511,56 @@ void MainWindow::setIsPlaying(bool isPlaying) {
     }
 }
 
+void MainWindow::setIsLoading(bool isLoading) {
+    if(isLoading) {
+        this->ui->playButton->setIcon(QIcon());
+        this->ui->playButton->setText("Loading...");
+    } else {
+        this->ui->playButton->setText("");
+        this->setIsPlaying(this->isPlaying);
+    }
+}
+
+void MainWindow::playerLoadingUpdated(bool isLoading) {
+    if(isLoading) {
+        if(!this->loadingTimer->isActive()) {
+            this->loadingTimer->start();
+        }
+    } else {
+        this->loadingTimer->stop();
+        this->setIsLoading(false);
+    }
+}
+
 void MainWindow::playerStateUpdated(std::shared_ptr<AudiobookFileProxy> abFile, bool isPlaying) {
     this->setCurrentlyPlayingFile(abFile);
     this->setIsPlaying(isPlaying);
+
+    if(isPlaying) {
+        this->ui->continueButton->setEnabled(true);
+    }
 }
 
 void MainWindow::playerTimeUpdated(std::shared_ptr<AudiobookFileProxy> abFile, long long currentTime) {
     this->setCurrentTime(currentTime);
 }
 
+std::shared_ptr<AudiobookProxy> MainWindow::getLoadedAudiobook() {
+    auto loadedFile = this->concretePlayer->getAudiobookFile();
+    if(loadedFile == nullptr) {
+        return nullptr;
+    }
+
+    QSqlQuery query;
+    query.prepare("SELECT * FROM audiobooks WHERE id = ?");
+    query.addBindValue(loadedFile->getRecord().value("audiobook_id").toInt());
+    if(!query.exec() || !query.next()) {
+        return nullptr;
+    }
+
+    return this->proxyManager->getAudiobookProxy(query.record());
+}
+
 void MainWindow::setCurrentlyPlayingFile(std::shared_ptr<AudiobookFileProxy> file) {
 
     this->currentlyPlayingFile = file;
@@ -276,10 +574,20 @@ void MainWindow::setCurrentlyPlayingFile(std::shared_ptr<AudiobookFileProxy> fil
     // set the slider max value if we have a parsed duration
     if(this->currentlyPlayingFile->isPropertyParsed()) {
         long long totalDuration = this->currentlyPlayingFile->getMediaDuration();
+
+        // some files only report their length once they are playing, until then there is nothing to seek in
+        if(totalDuration <= 0) {
+            this->ui->progressSlider->setMaximum(0);
+            this->ui->progressSlider->setEnabled(false);
+            this->ui->progressSlider->setToolTip("The length of this file is not known yet");
+            return;
+        }
+
         this->ui->progressSlider->setMaximum(static_cast<int>(totalDuration));
 
         // enable the slider
         this->ui->progressSlider->setEnabled(true);
+        this->ui->progressSlider->setToolTip("");
 
         // init the slider with the saved current time
         if(!file->currentTimeNull()) {
@@ -307,17 +615,147 @@ void MainWindow::audiobookFileStateUpdated(std::shared_ptr<AudiobookFileProxy> a
 
 void MainWindow::loadCurrentAudiobookIfExists() {
     auto audiobookId = this->settings->getCurrentAudiobookId();
-    if(!audiobookId != -1) {
-        Audiobook* currentModel = reinterpret_cast<Audiobook*>(this->ui->audiobookView->model());
-        for(int i = 0; i < currentModel->rowCount(); i++) {
-            if(currentModel->record(i).value("id").toInt() == audiobookId) {
-                QModelIndex currentIndex = currentModel->index(i, 0);
-                auto selectionModel = this->ui->audiobookView->selectionModel();
-                selectionModel->select(currentIndex, QItemSelectionModel::Select);
-                return;
-            }
+    if(audiobookId != -1) {
+        this->selectAudiobook(audiobookId);
+    }
+}
+
+void MainWindow::performJumpToTime() {
+    bool ok = false;
+    auto position = Core::parseTimeString(this->ui->jumpToTimeEdit->text(), &ok);
+
+    if(!ok) {
+        QToolTip::showText(this->ui->jumpToTimeEdit->mapToGlobal(QPoint(0, this->ui->jumpToTimeEdit->height())),
+                           "Use h:mm:ss, mm:ss or seconds",
+                           this->ui->jumpToTimeEdit);
+        return;
+    }
+
+    if(this->currentlyPlayingFile->getNullState()) {
+        return;
+    }
+
+    auto duration = this->currentlyPlayingFile->getMediaDuration();
+    if(duration > 0 && position > duration) {
+        position = duration;
+    }
+
+    this->concretePlayer->rememberSeekPosition();
+    this->concretePlayer->updateSeekPosition(position);
+    this->ui->jumpToTimeEdit->clear();
+}
+
+void MainWindow::performContinue() {
+    auto audiobookId = this->settings->getCurrentAudiobookId();
+    if(audiobookId == -1) {
+        return;
+    }
+
+    // the last audiobook could be hidden by the search or the collection filter
+    this->ui->searchEdit->clear();
+    this->ui->collectionChooser->setCurrentIndex(0);
+
+    // selecting the audiobook restores its file and position, without starting playback
+    this->selectAudiobook(audiobookId);
+}
+
+void MainWindow::performRevealNowPlaying() {
+    auto selectedAudiobooks = this->ui->audiobookView->selectionModel()->selectedIndexes();
+    if(selectedAudiobooks.isEmpty()) {
+        return;
+    }
+    this->ui->audiobookView->scrollTo(selectedAudiobooks.first(), QAbstractItemView::PositionAtCenter);
+
+    auto selectedFiles = this->ui->fileView->selectionModel()->selectedIndexes();
+    if(!selectedFiles.isEmpty()) {
+        this->ui->fileView->scrollTo(selectedFiles.first(), QAbstractItemView::PositionAtCenter);
+    }
+}
+
+void MainWindow::performCopyTimestamp() {
+    if(this->currentlyPlayingFile->getNullState()) {
+        return;
+    }
+
+    auto fileRecord = this->currentlyPlayingFile->getRecord();
+    auto fileName = fileRecord.value("name").toString();
+    if(this->settings->getPreferTags() && !fileRecord.value("title").isNull()) {
+        fileName = fileRecord.value("title").toString();
+    }
+
+    QString audiobookName = "";
+    QSqlQuery query;
+    query.prepare("SELECT name, title FROM audiobooks WHERE id = ?");
+    query.addBindValue(fileRecord.value("audiobook_id").toInt());
+    if(query.exec() && query.next()) {
+        audiobookName = query.value("name").toString();
+        if(this->settings->getPreferTags() && !query.value("title").isNull()) {
+            audiobookName = query.value("title").toString();
         }
+    }
 
+    // vlc keeps the position while paused, the saved one is used when nothing is loaded yet
+    long long time = this->concretePlayer->getCurrentTime();
+    if(time < 0) {
+        time = this->currentlyPlayingFile->getCurrentTime();
+    }
+
+    auto timestamp = Core::formatTimestamp(audiobookName, fileName, time);
+    QApplication::clipboard()->setText(timestamp);
+}
+
+void MainWindow::selectAudiobook(int audiobookId) {
+    Audiobook* currentModel = reinterpret_cast<Audiobook*>(this->ui->audiobookView->model());
+    for(int i = 0; i < currentModel->rowCount(); i++) {
+        if(currentModel->record(i).value("id").toInt() == audiobookId) {
+            QModelIndex currentIndex = currentModel->index(i, 0);
+            auto selectionModel = this->ui->audiobookView->selectionModel();
+            this->isSelectingAudiobook = true;
+            selectionModel->select(currentIndex, QItemSelectionModel::ClearAndSelect);
+            this->isSelectingAudiobook = false;
+            this->ui->audiobookView->scrollTo(currentIndex);
+            return;
+        }
+    }
+}
+
+void MainWindow::updateScanProgress(int filesDone, int filesTotal) {
+    if(filesTotal == 0) {
+        this->ui->scanProgressBar->hide();
+        return;
+    }
+
+    this->ui->scanProgressBar->setMaximum(filesTotal);
+    this->ui->scanProgressBar->setValue(filesDone);
+    this->ui->scanProgressBar->show();
+}
+
+void MainWindow::showScanProblems(QStringList paths) {
+    // the full list is in the tooltip, only a few names fit under the list
+    QStringList fileNames;
+    for(auto &path : paths.mid(0, MAX_LISTED_PROBLEM_FILES)) {
+        fileNames << QFileInfo(path).fileName();
+    }
+    if(paths.size() > MAX_LISTED_PROBLEM_FILES) {
+        fileNames << "...";
+    }
+
+    this->ui->scanProblemsLabel->setText("<span style=\"color: #b36b00;\">" + QString::number(paths.size()) +
+                                         " files could not be read: " + fileNames.join(", ").toHtmlEscaped() +
+                                         "</span> <a href=\"dismiss\">Dismiss</a>");
+    this->ui->scanProblemsLabel->setToolTip(paths.join("\n"));
+    this->ui->scanProblemsLabel->show();
+}
+
+void MainWindow::updateNoMatchesLabel() {
+    this->ui->noMatchesLabel->setVisible(this->audiobookModel->rowCount() == 0 && this->audiobookModel->isFiltered());
+}
+
+void MainWindow::updateQueueLabel(int count) {
+    if(count > 0) {
+        this->ui->queueLabel->setText("Queue: " + QString::number(count));
+    } else {
+        this->ui->queueLabel->setText("");
     }
 }
 
@@ -339,31 +777,100 @@ void MainWindow::updateFileView() {
 }
 
 void MainWindow::populateSpeedChoose() {
-    this->ui->speedChooser->addItem("0.5x", QVariant("0.5"));
-    this->ui->speedChooser->addItem("0.75x", QVariant("0.75"));
-    this->ui->speedChooser->addItem("1x", QVariant("1"));
-    this->ui->speedChooser->addItem("1.25x", QVariant("1.25"));
-    this->ui->speedChooser->addItem("1.5x", QVariant("1.5"));
-    this->ui->speedChooser->addItem("1.75x", QVariant("1.75"));
-    this->ui->speedChooser->addItem("2x", QVariant("2"));
-    this->ui->speedChooser->addItem("2.25x", QVariant("2.25"));
-    this->ui->speedChooser->addItem("2.5x", QVariant("2.5"));
+    this->updateSpeedPresets();
 
     auto speedFromSetting = this->settings->getSpeed();
     this->setSpeed(speedFromSetting);
 
+    connect(this->settings, &Core::Setting::speedPresetsUpdated, this, &MainWindow::updateSpeedPresets);
+
 
     connect(this->ui->speedChooser,
             static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
             [this](int index) -> void {
         auto currentData = this->ui->speedChooser->itemData(index);
         auto speedString = currentData.toString();
-        this->settings->setSpeed(speedString);
+
+        // speed is remembered for the audiobook that is playing, the global speed is only used when nothing is loaded
+        auto loadedAudiobook = this->getLoadedAudiobook();
+        if(loadedAudiobook != nullptr) {
+            loadedAudiobook->setSpeed(speedString);
+        } else {
+            this->settings->setSpeed(speedString);
+        }
 
         this->concretePlayer->setSpeed(speedString);
     });
 }
 
+void MainWindow::updateSpeedPresets() {
+    // the speed itself doesn't change, only the choices
+    QSignalBlocker blocker(this->ui->speedChooser);
+    auto currentSpeed = this->ui->speedChooser->currentData().toString();
+
+    this->ui->speedChooser->clear();
+    for(auto &preset : this->settings->getSpeedPresets()) {
+        this->ui->speedChooser->addItem(preset + "x", QVariant(preset));
+    }
+
+    if(!currentSpeed.isEmpty()) {
+        this->setSpeed(currentSpeed);
+    }
+}
+
+void MainWindow::populateCollectionChooser() {
+    QSignalBlocker blocker(this->ui->collectionChooser);
+    this->ui->collectionChooser->clear();
+
+    auto collections = Core::getCollections();
+    this->ui->collectionChooser->addItem("All Audiobooks", QVariant(-1));
+    for(auto &collection : collections) {
+        this->ui->collectionChooser->addItem(collection.name, QVariant(collection.id));
+    }
+    this->ui->collectionChooser->setVisible(!collections.isEmpty());
+
+    // the collection the list was limited to may be gone, then the whole library is shown again
+    auto currentIndex = this->ui->collectionChooser->findData(QVariant(this->audiobookModel->getCollectionFilter()));
+    if(currentIndex == -1) {
+        currentIndex = 0;
+    }
+    this->ui->collectionChooser->setCurrentIndex(currentIndex);
+
+    // directories may have moved between collections, so the list is filtered again either way
+    this->audiobookModel->setCollectionFilter(this->ui->collectionChooser->itemData(currentIndex).toInt());
+}
+
+void MainWindow::populateSortChooser() {
+    this->ui->sortChooser->addItem("Date Added", QVariant(SortByDateAdded));
+    this->ui->sortChooser->addItem("Title", QVariant(SortByTitle));
+    this->ui->sortChooser->addItem("Recently Played", QVariant(SortByRecentlyPlayed));
+    this->ui->sortChooser->addItem("Progress", QVariant(SortByProgress));
+    this->ui->sortChooser->addItem("Recently Added", QVariant(SortByRecentlyAdded));
+
+    auto currentIndex = this->ui->sortChooser->findData(QVariant(this->audiobookModel->getSortMode()));
+    if(currentIndex != -1) {
+        this->ui->sortChooser->setCurrentIndex(currentIndex);
+    }
+
+    connect(this->ui->sortChooser,
+            static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
+            [this](int index) -> void {
+        auto sortMode = this->ui->sortChooser->itemData(index).toInt();
+        this->audiobookModel->setSortMode(static_cast<AudiobookSortMode>(sortMode));
+    });
+}
+
+void MainWindow::performStepSpeed(bool faster, bool fine) {
+    auto currentSpeed = this->ui->speedChooser->currentData().toString();
+    auto nextSpeed = Core::stepSpeed(currentSpeed, this->settings->getSpeedPresets(), faster, fine);
+
+    // the speed chooser passes the new speed on to the player and saves it
+    this->setSpeed(nextSpeed);
+
+    // don't keep a chooser entry for every fine step that was passed through
+    this->updateSpeedPresets();
+}
+
 void MainWindow::setSpeed(QString speed) {
     int index = -1;
     for(int i = 0; i < this->ui->speedChooser->count(); i++) {
@@ -376,9 +883,18 @@ void MainWindow::setSpeed(QString speed) {
         }
     }
 
-    if(index != -1) {
-        this->ui->speedChooser->setCurrentIndex(index);
+    // a speed that isn't one of the presets is still shown
+    if(index == -1) {
+        this->ui->speedChooser->addItem(speed + "x", QVariant(speed));
+        index = this->ui->speedChooser->count() - 1;
     }
+
+    this->ui->speedChooser->setCurrentIndex(index);
+}
+
+void MainWindow::updateRepeatButtons() {
+    this->ui->repeatStartButton->setChecked(this->concretePlayer->isRepeatStartSet());
+    this->ui->repeatEndButton->setChecked(this->concretePlayer->isRepeatEndSet());
 }
 
 void MainWindow::setLabel(QLabel *pLabel, std::shared_ptr<AudiobookFileProxy> proxy, long long currentTime) {
@@ -393,9 +909,16 @@ void MainWindow::setLabel(QLabel *pLabel, std::shared_ptr<AudiobookFileProxy> pr
 
     if(currentTime >= 0) {
         // update the label
-        text += "<span>";
-        text += Core::convertTimeToString(currentTime);
-        text += "</span>";
+        long long duration = proxy->getNullState() ? -1 : proxy->getMediaDuration();
+        QString timeString = Core::convertTimeToString(currentTime);
+        if(this->settings->getShowRemainingTime() && duration > 0) {
+            timeString = "-" + Core::convertTimeToString(
+                    Core::remainingPlaybackTime(duration, currentTime, this->concretePlayer->getRate()));
+        }
+
+        text += "<a href=\"toggle-time\" style=\"text-decoration: none;\">";
+        text += timeString;
+        text += "</a>";
     }
 
     text += "</div>";
@@ -403,9 +926,346 @@ void MainWindow::setLabel(QLabel *pLabel, std::shared_ptr<AudiobookFileProxy> pr
     pLabel->setText(text);
 }
 
-// TODO perform rescan of audiobooks
+void MainWindow::performToggleTimeDisplay() {
+    this->settings->setShowRemainingTime(!this->settings->getShowRemainingTime());
+
+    if(!this->currentlyPlayingFile->getNullState()) {
+        this->setLabel(this->ui->currentlyPlayingLabel, this->currentlyPlayingFile,
+                       static_cast<long long>(this->currentTime));
+    }
+}
+
 void MainWindow::performRescan() {
+    // with a collection picked only its directories are rescanned
+    auto collectionId = this->audiobookModel->getCollectionFilter();
+    if(collectionId != -1) {
+        this->directoryModel->rescanCollection(collectionId);
+        return;
+    }
+
+    this->directoryModel->rescanAll();
+}
 
+void MainWindow::setLibraryStatsVisible(bool visible) {
+    if(visible) {
+        // only worked out when it is shown, it has to go through every file of the library
+        auto libraryStats = Core::summarizeListeningStats(Core::collectListeningStats(this->proxyManager));
+        auto hoursListened = QString::number(libraryStats.listenedDuration / 3600000.0, 'f', 1);
+
+        this->ui->libraryStatsLabel->setText("<p style=\"font-size: 16px; font-weight: bold;\">Your Library</p>" +
+                                             QString::number(libraryStats.audiobookCount) + " audiobooks<br>" +
+                                             QString::number(libraryStats.finishedCount) + " finished<br>" +
+                                             hoursListened + " hours listened");
+    }
+
+    this->ui->libraryStatsLabel->setVisible(visible);
+    this->ui->fileView->setVisible(!visible);
+}
+
+void MainWindow::updateRescanProgress(QString directory) {
+    if(directory.isEmpty()) {
+        this->ui->rescanLabel->hide();
+        this->audiobookModel->select();
+        return;
+    }
+
+    this->ui->rescanLabel->setText("Rescanning " + directory);
+    this->ui->rescanLabel->show();
+}
+
+void MainWindow::updateVerifyProgress(int filesChecked, int filesTotal) {
+    if(filesTotal == 0) {
+        this->ui->rescanLabel->hide();
+        return;
+    }
+
+    this->ui->rescanLabel->setText("Verifying files " + QString::number(filesChecked) +
+                                   "/" + QString::number(filesTotal));
+    this->ui->rescanLabel->show();
+}
+
+void MainWindow::performExportStats() {
+    auto target = QFileDialog::getSaveFileName(this, "Export Listening Statistics", "nodoka-stats.csv", "CSV Files (*.csv)");
+    if(target.isEmpty()) {
+        return;
+    }
+
+    auto stats = Core::collectListeningStats(this->proxyManager);
+    if(!Core::exportListeningStats(stats, target)) {
+        QMessageBox::critical(this, "Error", "Failed to write " + target);
+        return;
+    }
+
+    // the same totals as the library overview
+    auto libraryStats = Core::summarizeListeningStats(stats);
+    QMessageBox::information(this, "Listening Statistics",
+                             "Listened for " + QString::number(libraryStats.listenedDuration / 3600000.0, 'f', 1) +
+                             " hours, finished " + QString::number(libraryStats.finishedCount) + " of " +
+                             QString::number(libraryStats.audiobookCount) + " audiobooks.");
+}
+
+void MainWindow::performExportLibrary() {
+    auto target = QFileDialog::getSaveFileName(this, "Export Library", "nodoka-library.json", "JSON Files (*.json)");
+    if(target.isEmpty()) {
+        return;
+    }
+
+    if(!Core::exportLibrary(this->proxyManager, target)) {
+        QMessageBox::critical(this, "Error", "Failed to write " + target);
+    }
+}
+
+void MainWindow::performImportLibrary() {
+    auto source = QFileDialog::getOpenFileName(this, "Import Library", "", "JSON Files (*.json)");
+    if(source.isEmpty()) {
+        return;
+    }
+
+    Core::LibraryImportSummary summary;
+    QString errorMessage;
+    if(!Core::importLibrary(this->proxyManager, this->directoryModel, source, &summary, &errorMessage)) {
+        QMessageBox::critical(this, "Error", errorMessage);
+        return;
+    }
+
+    this->audiobookModel->select();
+
+    QString message = "Added " + QString::number(summary.directoriesAdded) + " directories, " +
+                      "restored the progress of " + QString::number(summary.filesUpdated) + " files and " +
+                      QString::number(summary.audiobooksUpdated) + " audiobooks.";
+    if(summary.missingPaths > 0) {
+        message += "\n\n" + QString::number(summary.missingPaths) +
+                   " of the imported paths don't exist on this computer.";
+    }
+    QMessageBox::information(this, "Import Library", message);
+}
+
+void MainWindow::performImportProgress() {
+    auto source = QFileDialog::getOpenFileName(this, "Import Progress", "", "CSV Files (*.csv)");
+    if(source.isEmpty()) {
+        return;
+    }
+
+    // match the rows first without writing anything, so the matches can be checked before they are applied
+    std::vector<Core::ProgressImportMatch> plan;
+    QString errorMessage;
+    if(!Core::importProgressCsv(this->proxyManager, source, true, &plan, &errorMessage)) {
+        QMessageBox::critical(this, "Error", errorMessage);
+        return;
+    }
+
+    int matchedCount = 0;
+    QStringList details;
+    for(auto &match : plan) {
+        auto prefix = "Line " + QString::number(match.line) + ": ";
+        if(match.position == -1) {
+            details << prefix + "the position could not be read";
+        } else if(match.audiobookId == -1) {
+            details << prefix + match.title + " has no matching audiobook";
+        } else {
+            matchedCount++;
+            auto progress = match.completeness >= 100 ? QString("finished") :
+                            "at " + Core::convertTimeToString(match.position);
+            details << prefix + match.title + " -> " + match.audiobookName + ", " + progress;
+        }
+    }
+
+    if(matchedCount == 0) {
+        QMessageBox messageBox(QMessageBox::Information, "Import Progress",
+                               "None of the rows matched an audiobook in the library.", QMessageBox::Ok, this);
+        messageBox.setDetailedText(details.join("\n"));
+        messageBox.exec();
+        return;
+    }
+
+    QString text = QString::number(matchedCount) + " of " + QString::number(plan.size()) +
+                   " rows matched an audiobook. Importing overwrites the progress of the matched audiobooks.";
+    QMessageBox messageBox(QMessageBox::Question, "Import Progress", text, QMessageBox::NoButton, this);
+    messageBox.setDetailedText(details.join("\n"));
+    auto importButton = messageBox.addButton("Import", QMessageBox::AcceptRole);
+    messageBox.addButton(QMessageBox::Cancel);
+    messageBox.exec();
+
+    if(messageBox.clickedButton() != importButton) {
+        return;
+    }
+
+    if(!Core::importProgressCsv(this->proxyManager, source, false, &plan, &errorMessage)) {
+        QMessageBox::critical(this, "Error", errorMessage);
+        return;
+    }
+
+    this->audiobookModel->select();
+    this->updateFileView();
+}
+
+void MainWindow::performRemoveMissingFiles() {
+    QSqlQuery query;
+    if(!query.exec("SELECT * FROM audiobooks")) {
+        qWarning() << "audiobook list query failed: " << query.lastError().databaseText();
+        return;
+    }
+
+    std::vector<std::shared_ptr<AudiobookProxy>> audiobooks;
+    size_t missingCount = 0;
+    while(query.next()) {
+        auto audiobook = this->proxyManager->getAudiobookProxy(query.record());
+        missingCount += audiobook->getMissingFiles().size();
+        audiobooks.push_back(audiobook);
+    }
+
+    if(missingCount == 0) {
+        QMessageBox::information(this, "Remove Missing Files", "All files in the library were found.");
+        return;
+    }
+
+    auto answer = QMessageBox::question(this, "Remove Missing Files",
+                                        "Remove " + QString::number(missingCount) +
+                                        " files that can no longer be found from the library?");
+    if(answer != QMessageBox::Yes) {
+        return;
+    }
+
+    std::vector<std::shared_ptr<AudiobookProxy>> emptyAudiobooks;
+    for(auto &audiobook : audiobooks) {
+        if(audiobook->removeMissingFiles() > 0 && audiobook->getFilesForAudiobook().empty()) {
+            emptyAudiobooks.push_back(audiobook);
+        }
+    }
+
+    if(!emptyAudiobooks.empty()) {
+        answer = QMessageBox::question(this, "Remove Missing Files",
+                                       QString::number(emptyAudiobooks.size()) +
+                                       " audiobooks have no files left. Remove them as well?");
+        if(answer == QMessageBox::Yes) {
+            for(auto &audiobook : emptyAudiobooks) {
+                audiobook->remove();
+            }
+        }
+    }
+
+    this->audiobookModel->select();
+    this->updateFileView();
+}
+
+void MainWindow::performFindDuplicates() {
+    auto duplicateGroups = Core::findDuplicateAudiobooks(this->proxyManager);
+    if(duplicateGroups.empty()) {
+        QMessageBox::information(this, "Find Duplicate Audiobooks", "No duplicate audiobooks were found.");
+        return;
+    }
+
+    for(auto &audiobooks : duplicateGroups) {
+        // the copy with the most progress is the one to keep
+        auto keep = *std::max_element(audiobooks.begin(), audiobooks.end(),
+                                      [](std::shared_ptr<AudiobookProxy> first, std::shared_ptr<AudiobookProxy> second) {
+                                          return first->getCompleteness() < second->getCompleteness();
+                                      });
+
+        QString text = "These audiobooks contain the same files:<ul>";
+        for(auto &audiobook : audiobooks) {
+            text += "<li>" + audiobook->getPath().toHtmlEscaped() +
+                    " (" + QString::number(audiobook->getCompleteness()) + "%)</li>";
+        }
+        text += "</ul>Merging keeps " + keep->getPath().toHtmlEscaped() +
+                " and removes the other copies from the library, no files are deleted. "
+                "Rescans skip the removed copies until their folder is added again.";
+
+        QMessageBox messageBox(QMessageBox::Question, "Find Duplicate Audiobooks", text, QMessageBox::NoButton, this);
+        auto mergeButton = messageBox.addButton("Merge", QMessageBox::AcceptRole);
+        messageBox.addButton("Ignore", QMessageBox::RejectRole);
+        messageBox.exec();
+
+        if(messageBox.clickedButton() == mergeButton) {
+            for(auto &audiobook : audiobooks) {
+                if(audiobook != keep) {
+                    Core::markMergedDuplicate(audiobook->getPath());
+                    audiobook->remove();
+                }
+            }
+        }
+    }
+
+    this->audiobookModel->select();
+    this->updateFileView();
+}
+
+static QString countRows(QString table) {
+    QSqlQuery query;
+    if(!query.exec("SELECT COUNT(*) FROM " + table) || !query.next()) {
+        qWarning() << "diagnostics count query failed: " << query.lastError().driverText()
+                   << ", " << query.lastError().databaseText();
+        return "unknown";
+    }
+
+    return query.value(0).toString();
+}
+
+void MainWindow::performShowDiagnostics() {
+    // vlc looks in its default location unless the plugin path is set
+    auto pluginPath = QString::fromLocal8Bit(qgetenv("VLC_PLUGIN_PATH"));
+    if(pluginPath.isEmpty()) {
+        pluginPath = "default";
+    }
+
+    QStringList lines;
+    lines << "VLC version: " + Core::ConcretePlayer::getVlcVersion();
+    lines << "VLC plugin path: " + pluginPath;
+    lines << "Data directory: " + QDir::toNativeSeparators(Core::getSettingPath());
+    lines << "Database: " + QDir::toNativeSeparators(QSqlDatabase::database().databaseName());
+    lines << "Scanning directories: " + countRows("directories");
+    lines << "Audiobooks: " + countRows("audiobooks");
+    lines << "Files: " + countRows("audiobook_file");
+    auto text = lines.join("\n");
+
+    QMessageBox messageBox(QMessageBox::Information, "Diagnostics", text, QMessageBox::NoButton, this);
+    messageBox.setTextInteractionFlags(Qt::TextSelectableByMouse);
+    auto copyButton = messageBox.addButton("Copy", QMessageBox::ActionRole);
+    auto saveReportButton = messageBox.addButton("Save Report...", QMessageBox::ActionRole);
+    messageBox.addButton(QMessageBox::Close);
+    messageBox.exec();
+
+    if(messageBox.clickedButton() == copyButton) {
+        QApplication::clipboard()->setText(text);
+    } else if(messageBox.clickedButton() == saveReportButton) {
+        this->performSaveDiagnosticReport();
+    }
+}
+
+void MainWindow::performSaveDiagnosticReport() {
+    auto destination = QFileDialog::getSaveFileName(this, "Save Diagnostic Report", "nodoka-report.txt",
+                                                    "Text Files (*.txt)");
+    if(destination.isEmpty()) {
+        return;
+    }
+
+    // the report is meant to be shared, so it has counts and versions but no paths or names
+    QFile file(destination);
+    if(!file.open(QIODevice::WriteOnly | QIODevice::Truncate | QIODevice::Text)) {
+        QMessageBox::critical(this, "Error", "Could not write the report to " + destination);
+        return;
+    }
+
+    QTextStream out(&file);
+    out.setCodec("UTF-8");
+    out << "Nodoka diagnostic report\n";
+    out << "Created: " << QDateTime::currentDateTimeUtc().toString(Qt::ISODate) << "\n";
+    out << "Operating system: " << QSysInfo::prettyProductName() << " (" << QSysInfo::currentCpuArchitecture() << ")\n";
+    out << "Qt version: " << qVersion() << "\n";
+    out << "VLC version: " << Core::ConcretePlayer::getVlcVersion() << "\n";
+    out << "VLC plugin path set: " << (qgetenv("VLC_PLUGIN_PATH").isEmpty() ? "no" : "yes") << "\n";
+    out << "Scanning directories: " << countRows("directories") << "\n";
+    out << "Audiobooks: " << countRows("audiobooks") << "\n";
+    out << "Files: " << countRows("audiobook_file") << "\n";
+    out << "\nRecent log messages:\n";
+    for(auto &line : Core::getRecentLogLines(this->proxyManager)) {
+        out << line << "\n";
+    }
+    file.close();
+
+    if(file.error() != QFile::NoError) {
+        QMessageBox::critical(this, "Error", "Could not write the report to " + destination);
+    }
 }
 
 void MainWindow::performAudiobookAdd() {
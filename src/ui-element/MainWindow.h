@@ -7,6 +7,7 @@
 
 
 #include <QMenu>
+#include <QTimer>
 #include <QtWidgets/QWidget>
 #include <QMainWindow>
 #include <QAbstractItemView>
@@ -41,6 +42,7 @@ private:
     SettingsForm* settingsForm;
 
     Core::Setting* settings;
+    std::shared_ptr<ProxyManager> proxyManager;
 
     //menus
     QMenu* audiobookMenu;
@@ -52,9 +54,18 @@ private:
 
     // logical states
     bool isPlaying;
+
+    // short buffering hiccups shouldn't make the play button flicker, so loading is only shown after a delay
+    QTimer* loadingTimer;
+    void setIsLoading(bool isLoading);
+    std::shared_ptr<AudiobookProxy> selectedAudiobook;
+    // set while selectAudiobook changes the selection, as opposed to the user clicking an audiobook
+    bool isSelectingAudiobook;
     std::shared_ptr<AudiobookFileProxy> currentlyPlayingFile;
     double currentTime;
     void setCurrentlyPlayingFile(std::shared_ptr<AudiobookFileProxy> file);
+    // the audiobook of the file in the player, nullptr if nothing is loaded
+    std::shared_ptr<AudiobookProxy> getLoadedAudiobook();
 
 
 
@@ -72,25 +83,54 @@ public:
     void performSettings();
     void performRescan();
     void performExit();
+    void performContinue();
+    void performJumpToTime();
+    void performRevealNowPlaying();
+    void performToggleTimeDisplay();
+    void performCopyTimestamp();
+    void performStepSpeed(bool faster, bool fine);
 
     // helper functions
     void menuSetup();
     void setup();
     void loadCurrentAudiobookIfExists();
+    void selectAudiobook(int audiobookId);
+    void updateQueueLabel(int count);
+    void updateNoMatchesLabel();
+    void updateScanProgress(int filesDone, int filesTotal);
+    void showScanProblems(QStringList paths);
+    void updateRescanProgress(QString directory);
+    void updateVerifyProgress(int filesChecked, int filesTotal);
+
+    // the overview of the whole library, shown while no audiobook is selected
+    void setLibraryStatsVisible(bool visible);
 
     void setSelectedFile(QString path);
     void setCurrentTime(long long currentTime);
     void setIsPlaying(bool isPlaying);
     void updateFileView();
     void populateSpeedChoose();
+    void updateSpeedPresets();
+    void populateSortChooser();
+    void populateCollectionChooser();
     void setSpeed(QString speed);
+    void updateRepeatButtons();
 
 
 public slots:
     void audiobookFileStateUpdated(std::shared_ptr<AudiobookFileProxy> abFile);
     void playerStateUpdated(std::shared_ptr<AudiobookFileProxy> abFile, bool isPlaying);
+    void playerLoadingUpdated(bool isLoading);
     void playerTimeUpdated(std::shared_ptr<AudiobookFileProxy> abFile, long long currentTime);
     void performAudiobookAdd();
+    void performExportStats();
+    void performExportLibrary();
+    void performImportLibrary();
+    void performImportProgress();
+    void performRemoveMissingFiles();
+    void performFindDuplicates();
+    void performShowDiagnostics();
+    void performSaveDiagnosticReport();
 
     void setLabel(QLabel *pLabel,
                   std::shared_ptr<AudiobookFileProxy> proxy = std::shared_ptr<AudiobookFileProxy>(new AudiobookFileProxy()),
@@ -108,6 +148,20 @@ const static char* AB_ITEM_STYLESHEET = ""
         "font-size: 14px;"
         "font-weight: bold;"
         "}"
+        ""
+        "span.finished {"
+        "color: #4c8c2b;"
+        "font-weight: bold;"
+        "}"
+        ""
+        "span.gaps {"
+        "color: #b36b00;"
+        "}"
+        ""
+        "span.new {"
+        "color: #2b6c8c;"
+        "font-weight: bold;"
+        "}"
 ;
 
 const static char* LIST_VIEW_STYLESHEET = ""
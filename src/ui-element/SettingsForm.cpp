@@ -4,14 +4,57 @@
 
 #include <QtWidgets/QFileDialog>
 #include <QtWidgets/QMessageBox>
+#include <QInputDialog>
+#include <QSqlQuery>
+#include <QSqlError>
+#include <QDebug>
+#include <QHash>
+#include <src/core/Util.h>
+#include <src/core/Collections.h>
 #include "SettingsForm.h"
 
-SettingsForm::SettingsForm(Directory* directoryModel, QWidget *parent) :
+// choice in the collection picker that takes directories out of their collection
+const static char* NO_COLLECTION_NAME = "(No Collection)";
+
+// split a comma separated list typed into a line edit, empty entries are dropped
+static QStringList splitList(QString text) {
+    QStringList items;
+    for(auto &item : text.split(',')) {
+        if(!item.trimmed().isEmpty()) {
+            items << item.trimmed();
+        }
+    }
+
+    return items;
+}
+
+// audiobooks found in the given scanning directories, and how many of them have been started
+static void countAudiobooksIn(QStringList paths, int* audiobookCount, int* startedCount) {
+    for(auto &path : paths) {
+        QSqlQuery query;
+        query.prepare("SELECT COUNT(*), SUM(CASE WHEN completeness > 0 THEN 1 ELSE 0 END) "
+                      "FROM audiobooks WHERE directory = ?");
+        query.addBindValue(path);
+        if(!query.exec() || !query.next()) {
+            qWarning() << "audiobook count query failed: " << query.lastError().driverText()
+                       << ", " << query.lastError().databaseText();
+            continue;
+        }
+
+        *audiobookCount += query.value(0).toInt();
+        *startedCount += query.value(1).toInt();
+    }
+}
+
+SettingsForm::SettingsForm(Directory* directoryModel, Core::Setting* setting, QWidget *parent) :
   QWidget(parent), ui(new Ui::SettingsForm()) {
     ui->setupUi(this);
-    ui->listView->setModel(directoryModel);
-    ui->listView->setSelectionMode(QAbstractItemView::SelectionMode::SingleSelection);
+    this->directoryTreeModel = new QStandardItemModel(this);
+    ui->directoryView->setModel(this->directoryTreeModel);
+    ui->directoryView->setSelectionMode(QAbstractItemView::SelectionMode::SingleSelection);
+    ui->directoryView->setEditTriggers(QAbstractItemView::NoEditTriggers);
     this->directoryModel = directoryModel;
+    this->setting = setting;
     this->setup();
 }
 
@@ -27,6 +70,217 @@ void SettingsForm::setup() {
     connect(this->ui->removeDirectoryButton, &QPushButton::clicked, this, &SettingsForm::performRemoveDirectory);
     connect(this->ui->closeButton, &QPushButton::clicked, this, &SettingsForm::performClose);
     connect(this->ui->rescanButton, &QPushButton::clicked, this, &SettingsForm::performRescan);
+    connect(this->ui->toggleDirectoryButton, &QPushButton::clicked, this, &SettingsForm::performToggleDirectory);
+    connect(this->ui->collectionButton, &QPushButton::clicked, this, &SettingsForm::performSetCollection);
+
+    // every change to the directories ends with a select(), which resets the model
+    connect(this->directoryModel, &QAbstractItemModel::modelReset, [this]() {
+        this->rebuildDirectoryTree();
+    });
+    connect(this->ui->directoryView, &QTreeView::collapsed, [this](const QModelIndex &index) {
+        this->collapsedGroups.insert(index.data(Qt::UserRole).toString());
+    });
+    connect(this->ui->directoryView, &QTreeView::expanded, [this](const QModelIndex &index) {
+        this->collapsedGroups.remove(index.data(Qt::UserRole).toString());
+    });
+    this->rebuildDirectoryTree();
+
+    this->setupGeneralTab();
+}
+
+void SettingsForm::setupGeneralTab() {
+    this->ui->preferTagsCheckBox->setChecked(this->setting->getPreferTags());
+    connect(this->ui->preferTagsCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setPreferTags);
+
+    this->ui->showTechnicalDetailsCheckBox->setChecked(this->setting->getShowTechnicalDetails());
+    connect(this->ui->showTechnicalDetailsCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setShowTechnicalDetails);
+
+    this->ui->chapterTitlesCheckBox->setChecked(this->setting->getChapterTitlesFromFileNames());
+    connect(this->ui->chapterTitlesCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setChapterTitlesFromFileNames);
+
+    // each check box stands for one flag of the file list columns
+    QList<QPair<QCheckBox*, Core::FileListColumn>> columnCheckBoxes;
+    columnCheckBoxes << qMakePair(this->ui->fileListDurationCheckBox, Core::FileListDuration)
+                     << qMakePair(this->ui->fileListProgressPercentCheckBox, Core::FileListProgressPercent)
+                     << qMakePair(this->ui->fileListProgressBarCheckBox, Core::FileListProgressBar)
+                     << qMakePair(this->ui->fileListCompleteMarkCheckBox, Core::FileListCompleteMark);
+    auto columns = this->setting->getFileListColumns();
+    for(auto &columnCheckBox : columnCheckBoxes) {
+        auto column = columnCheckBox.second;
+        columnCheckBox.first->setChecked(columns & column);
+        connect(columnCheckBox.first, &QCheckBox::toggled, [this, column](bool checked) {
+            auto columns = this->setting->getFileListColumns();
+            this->setting->setFileListColumns(checked ? columns | column : columns & ~column);
+        });
+    }
+
+    this->ui->watchDirectoriesCheckBox->setChecked(this->setting->getWatchDirectories());
+    connect(this->ui->watchDirectoriesCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setWatchDirectories);
+
+    this->ui->scanOnStartupCheckBox->setChecked(this->setting->getScanOnStartup());
+    connect(this->ui->scanOnStartupCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setScanOnStartup);
+
+    this->ui->combineDiscFoldersCheckBox->setChecked(this->setting->getCombineDiscFolders());
+    connect(this->ui->combineDiscFoldersCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setCombineDiscFolders);
+
+    this->ui->fileSortComboBox->addItem("Name", QVariant(Core::FileSortName));
+    this->ui->fileSortComboBox->addItem("Track number", QVariant(Core::FileSortTrackTag));
+    this->ui->fileSortComboBox->addItem("Date modified", QVariant(Core::FileSortModified));
+    auto fileSortIndex = this->ui->fileSortComboBox->findData(QVariant(this->setting->getFileSortMode()));
+    if(fileSortIndex != -1) {
+        this->ui->fileSortComboBox->setCurrentIndex(fileSortIndex);
+    }
+    connect(this->ui->fileSortComboBox,
+            static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
+            [this](int index) {
+        this->setting->setFileSortMode(this->ui->fileSortComboBox->itemData(index).toInt());
+    });
+
+    this->ui->normalizeVolumeCheckBox->setChecked(this->setting->getNormalizeVolume());
+    connect(this->ui->normalizeVolumeCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setNormalizeVolume);
+
+    this->ui->confirmDestructiveCheckBox->setChecked(this->setting->getConfirmDestructive());
+    connect(this->ui->confirmDestructiveCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setConfirmDestructive);
+
+    this->ui->autoPlayOnSelectCheckBox->setChecked(this->setting->getAutoPlayOnSelect());
+    connect(this->ui->autoPlayOnSelectCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setAutoPlayOnSelect);
+
+    this->ui->pauseOnOutputRemovedCheckBox->setChecked(this->setting->getPauseOnOutputRemoved());
+    connect(this->ui->pauseOnOutputRemovedCheckBox, &QCheckBox::toggled, this->setting, &Core::Setting::setPauseOnOutputRemoved);
+
+    this->ui->speedPresetsEdit->setText(this->setting->getSpeedPresets().join(", "));
+    connect(this->ui->speedPresetsEdit, &QLineEdit::editingFinished, this, &SettingsForm::performSaveSpeedPresets);
+
+    this->ui->accentColorEdit->setText(this->setting->getAccentColor());
+    connect(this->ui->accentColorEdit, &QLineEdit::editingFinished, this, &SettingsForm::performSaveAccentColor);
+
+    this->ui->extraFileExtensionsEdit->setText(this->setting->getExtraFileExtensions().join(", "));
+    this->ui->ignoredFileExtensionsEdit->setText(this->setting->getIgnoredFileExtensions().join(", "));
+    connect(this->ui->extraFileExtensionsEdit, &QLineEdit::editingFinished, this, &SettingsForm::performSaveFileExtensions);
+    connect(this->ui->ignoredFileExtensionsEdit, &QLineEdit::editingFinished, this, &SettingsForm::performSaveFileExtensions);
+
+    this->ui->minFileDurationSpinBox->setValue(this->setting->getMinFileDurationSeconds());
+    connect(this->ui->minFileDurationSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setMinFileDurationSeconds);
+
+    this->ui->reselectBehaviorComboBox->addItem("Pause or resume it", QVariant(Core::ReselectTogglePlayPause));
+    this->ui->reselectBehaviorComboBox->addItem("Start it over", QVariant(Core::ReselectRestart));
+    this->ui->reselectBehaviorComboBox->addItem("Do nothing", QVariant(Core::ReselectIgnore));
+    auto reselectIndex = this->ui->reselectBehaviorComboBox->findData(QVariant(this->setting->getReselectBehavior()));
+    if(reselectIndex != -1) {
+        this->ui->reselectBehaviorComboBox->setCurrentIndex(reselectIndex);
+    }
+    connect(this->ui->reselectBehaviorComboBox,
+            static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
+            [this](int index) {
+        this->setting->setReselectBehavior(this->ui->reselectBehaviorComboBox->itemData(index).toInt());
+    });
+
+    this->ui->bookEndBehaviorComboBox->addItem("Play the next queued audiobook", QVariant(Core::BookEndNextInQueue));
+    this->ui->bookEndBehaviorComboBox->addItem("Play the next unfinished audiobook in the same folder",
+                                               QVariant(Core::BookEndNextUnfinished));
+    this->ui->bookEndBehaviorComboBox->addItem("Start the audiobook over", QVariant(Core::BookEndLoop));
+    this->ui->bookEndBehaviorComboBox->addItem("Stop", QVariant(Core::BookEndStop));
+    auto bookEndIndex = this->ui->bookEndBehaviorComboBox->findData(QVariant(this->setting->getBookEndBehavior()));
+    if(bookEndIndex != -1) {
+        this->ui->bookEndBehaviorComboBox->setCurrentIndex(bookEndIndex);
+    }
+    connect(this->ui->bookEndBehaviorComboBox,
+            static_cast<void (QComboBox::*)(int)>(&QComboBox::currentIndexChanged),
+            [this](int index) {
+        this->setting->setBookEndBehavior(this->ui->bookEndBehaviorComboBox->itemData(index).toInt());
+    });
+
+    this->ui->defaultVolumeSpinBox->setValue(this->setting->getDefaultVolume());
+    connect(this->ui->defaultVolumeSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setDefaultVolume);
+
+    this->ui->fadeDurationSpinBox->setValue(this->setting->getFadeDuration());
+    connect(this->ui->fadeDurationSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setFadeDuration);
+
+    this->ui->scanWorkersSpinBox->setRange(MIN_SCAN_WORKERS, MAX_SCAN_WORKERS);
+    this->ui->scanWorkersSpinBox->setValue(this->setting->getScanWorkers());
+    connect(this->ui->scanWorkersSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setScanWorkers);
+
+    this->ui->progressSaveIntervalSpinBox->setValue(this->setting->getProgressSaveInterval());
+    connect(this->ui->progressSaveIntervalSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setProgressSaveInterval);
+
+    this->ui->tickIntervalSpinBox->setRange(MIN_TICK_INTERVAL_MS, MAX_TICK_INTERVAL_MS);
+    this->ui->tickIntervalSpinBox->setValue(this->setting->getTickInterval());
+    connect(this->ui->tickIntervalSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setTickInterval);
+
+    this->ui->smartResumeSpinBox->setValue(this->setting->getSmartResumeSeconds());
+    connect(this->ui->smartResumeSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setSmartResumeSeconds);
+
+    this->ui->pauseRewindSpinBox->setValue(this->setting->getPauseRewindSeconds());
+    connect(this->ui->pauseRewindSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setPauseRewindSeconds);
+
+    this->ui->completeToleranceSecondsSpinBox->setValue(this->setting->getCompleteToleranceSeconds());
+    connect(this->ui->completeToleranceSecondsSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setCompleteToleranceSeconds);
+
+    this->ui->completeTolerancePercentSpinBox->setValue(this->setting->getCompleteTolerancePercent());
+    connect(this->ui->completeTolerancePercentSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setCompleteTolerancePercent);
+
+    this->ui->listenedThresholdSpinBox->setRange(MIN_LISTENED_THRESHOLD_PERCENT, MAX_LISTENED_THRESHOLD_PERCENT);
+    this->ui->listenedThresholdSpinBox->setValue(this->setting->getListenedThresholdPercent());
+    connect(this->ui->listenedThresholdSpinBox,
+            static_cast<void (QSpinBox::*)(int)>(&QSpinBox::valueChanged),
+            this->setting, &Core::Setting::setListenedThresholdPercent);
+}
+
+void SettingsForm::performSaveSpeedPresets() {
+    // editingFinished also fires when the field only lost focus
+    if(this->ui->speedPresetsEdit->text() == this->setting->getSpeedPresets().join(", ")) {
+        return;
+    }
+
+    auto presets = splitList(this->ui->speedPresetsEdit->text());
+
+    if(!this->setting->setSpeedPresets(presets)) {
+        QMessageBox::critical(this, "Error", "Speed presets must be up to six speeds between 0.5 and 2.5");
+    }
+
+    // show what was actually stored
+    this->ui->speedPresetsEdit->setText(this->setting->getSpeedPresets().join(", "));
+}
+
+void SettingsForm::performSaveAccentColor() {
+    if(!this->setting->setAccentColor(this->ui->accentColorEdit->text())) {
+        QMessageBox::critical(this, "Error", "The accent color must be a hex color like #FEDB53 or #fd5, light enough "
+                                             "for black text on it to stay readable (a contrast of at least 4.5:1)");
+    }
+
+    // show what was actually stored
+    this->ui->accentColorEdit->setText(this->setting->getAccentColor());
+}
+
+void SettingsForm::performSaveFileExtensions() {
+    if(!this->setting->setExtraFileExtensions(splitList(this->ui->extraFileExtensionsEdit->text())) ||
+       !this->setting->setIgnoredFileExtensions(splitList(this->ui->ignoredFileExtensionsEdit->text()))) {
+        QMessageBox::critical(this, "Error", "Extensions must be lowercase letters and digits without the dot, e.g. mka");
+    }
+
+    // show what was actually stored
+    this->ui->extraFileExtensionsEdit->setText(this->setting->getExtraFileExtensions().join(", "));
+    this->ui->ignoredFileExtensionsEdit->setText(this->setting->getIgnoredFileExtensions().join(", "));
 }
 
 void SettingsForm::performAddDirectory() {
@@ -39,14 +293,39 @@ void SettingsForm::performAddDirectory() {
 }
 
 void SettingsForm::performRemoveDirectory() {
-    // get the currently selected item
-    auto indexes = this->ui->listView->selectionModel()->selectedIndexes();
-    if(indexes.size() == 0) {
+    auto paths = this->selectedDirectoryPaths();
+    if(paths.isEmpty()) {
         QMessageBox *messageBox = new QMessageBox();
         messageBox->critical(0, "Error", "You must select a directory to remove");
-    } else {
-        QModelIndex index = this->ui->listView->selectionModel()->selectedIndexes().first();
-        this->directoryModel->removeDirectory(index);
+        return;
+    }
+
+    if(this->setting->getConfirmDestructive()) {
+        int audiobookCount = 0;
+        int startedCount = 0;
+        countAudiobooksIn(paths, &audiobookCount, &startedCount);
+
+        QString question = paths.size() > 1 ?
+                           "Remove all " + QString::number(paths.size()) + " directories in this folder?" :
+                           "Remove " + paths.first() + "?";
+        question += "\n\n" + QString::number(audiobookCount) + " audiobooks will be removed from the library";
+        if(startedCount > 0) {
+            question += ", and the listening progress of " + QString::number(startedCount) + " of them will be lost";
+        }
+        question += ". No files are deleted.";
+
+        auto answer = QMessageBox::question(this, "Remove Directories", question);
+        if(answer != QMessageBox::Yes) {
+            return;
+        }
+    }
+
+    // removing a directory reloads the model, so each one is looked up again
+    for(auto &path : paths) {
+        auto index = this->directoryIndexForPath(path);
+        if(index.isValid()) {
+            this->directoryModel->removeDirectory(index);
+        }
     }
 }
 
@@ -55,13 +334,175 @@ void SettingsForm::performClose() {
 }
 
 void SettingsForm::performRescan() {
-    auto indexes = this->ui->listView->selectionModel()->selectedIndexes();
-    if(indexes.size() == 0) {
+    auto paths = this->selectedDirectoryPaths();
+    if(paths.isEmpty()) {
         QMessageBox *messageBox = new QMessageBox();
         messageBox->critical(0, "Error", "You must select a directory to rescan");
+        return;
+    }
+
+    for(auto &path : paths) {
+        auto index = this->directoryIndexForPath(path);
+        if(index.isValid()) {
+            emit this->directoryModel->directoryRescan(this->directoryModel->record(index.row()));
+        }
+    }
+}
+
+void SettingsForm::performToggleDirectory() {
+    auto paths = this->selectedDirectoryPaths();
+    if(paths.isEmpty()) {
+        QMessageBox *messageBox = new QMessageBox();
+        messageBox->critical(0, "Error", "You must select a directory to enable or disable");
+        return;
+    }
+
+    // a group is enabled as a whole unless all of it is enabled already
+    bool enable = false;
+    for(auto &path : paths) {
+        auto index = this->directoryIndexForPath(path);
+        if(index.isValid() && !this->directoryModel->isDirectoryEnabled(index)) {
+            enable = true;
+        }
+    }
+
+    for(auto &path : paths) {
+        auto index = this->directoryIndexForPath(path);
+        if(index.isValid() && this->directoryModel->isDirectoryEnabled(index) != enable) {
+            this->directoryModel->setDirectoryEnabled(index, enable);
+        }
+    }
+}
+
+void SettingsForm::performSetCollection() {
+    auto paths = this->selectedDirectoryPaths();
+    if(paths.isEmpty()) {
+        QMessageBox *messageBox = new QMessageBox();
+        messageBox->critical(0, "Error", "You must select a directory to put in a collection");
+        return;
+    }
+
+    // typing a name that isn't in the list creates a new collection
+    QStringList names;
+    names << NO_COLLECTION_NAME;
+    for(auto &collection : Core::getCollections()) {
+        names << collection.name;
+    }
+
+    bool ok = false;
+    auto name = QInputDialog::getItem(this, "Set Collection", "Collection:", names, 0, true, &ok).trimmed();
+    if(!ok || name.isEmpty()) {
+        return;
+    }
+
+    int collectionId = -1;
+    if(name != NO_COLLECTION_NAME) {
+        collectionId = Core::findOrCreateCollection(name);
+        if(collectionId == -1) {
+            QMessageBox::critical(this, "Error", "Failed to create the collection " + name);
+            return;
+        }
+    }
+
+    for(auto &path : paths) {
+        auto index = this->directoryIndexForPath(path);
+        if(index.isValid()) {
+            this->directoryModel->setDirectoryCollection(index, collectionId);
+        }
+    }
+}
+
+void SettingsForm::rebuildDirectoryTree() {
+    QSqlQuery query;
+    QHash<QString, int> audiobookCounts;
+    if(query.exec("SELECT directory, COUNT(*) AS audiobook_count FROM audiobooks GROUP BY directory")) {
+        while(query.next()) {
+            audiobookCounts.insert(query.value("directory").toString(), query.value("audiobook_count").toInt());
+        }
+    }
+
+    QHash<QString, QString> collectionNames;
+    if(query.exec("SELECT directories.full_path, collections.name FROM directories "
+                  "JOIN collections ON collections.id = directories.collection_id")) {
+        while(query.next()) {
+            collectionNames.insert(query.value(0).toString(), query.value(1).toString());
+        }
+    }
+
+    QStringList paths;
+    for(int i = 0; i < this->directoryModel->rowCount(); i++) {
+        paths << this->directoryModel->record(i).value("full_path").toString();
+    }
+
+    this->directoryTreeModel->clear();
+    auto groups = Core::groupDirectoriesByParent(paths);
+    for(auto it = groups.constBegin(); it != groups.constEnd(); ++it) {
+        QList<QStandardItem*> directoryItems;
+        int groupAudiobookCount = 0;
+        for(auto &path : it.value()) {
+            // the directory model adds the disabled marker to the path
+            auto row = this->directoryIndexForPath(path).row();
+            auto pathIndex = this->directoryModel->index(row, this->directoryModel->fieldIndex("full_path"));
+            auto label = this->directoryModel->data(pathIndex, Qt::DisplayRole).toString();
+            auto audiobookCount = audiobookCounts.value(path, 0);
+            groupAudiobookCount += audiobookCount;
+
+            if(collectionNames.contains(path)) {
+                label += "  [" + collectionNames.value(path) + "]";
+            }
+
+            auto item = new QStandardItem(label + "  (" + QString::number(audiobookCount) + " audiobooks)");
+            item->setData(path, Qt::UserRole);
+            directoryItems << item;
+        }
+
+        // a folder with a single directory in it doesn't need a group
+        if(directoryItems.size() == 1) {
+            this->directoryTreeModel->appendRow(directoryItems.first());
+            continue;
+        }
+
+        auto groupItem = new QStandardItem(it.key() + "  (" + QString::number(directoryItems.size()) + " directories, " +
+                                           QString::number(groupAudiobookCount) + " audiobooks)");
+        groupItem->setData(it.key(), Qt::UserRole);
+        groupItem->setData(true, Qt::UserRole + 1);
+        for(auto item : directoryItems) {
+            groupItem->appendRow(item);
+        }
+        this->directoryTreeModel->appendRow(groupItem);
+
+        if(!this->collapsedGroups.contains(it.key())) {
+            this->ui->directoryView->expand(groupItem->index());
+        }
+    }
+}
+
+QStringList SettingsForm::selectedDirectoryPaths() {
+    QStringList paths;
+
+    auto indexes = this->ui->directoryView->selectionModel()->selectedIndexes();
+    if(indexes.isEmpty()) {
+        return paths;
+    }
+
+    auto item = this->directoryTreeModel->itemFromIndex(indexes.first());
+    if(item->data(Qt::UserRole + 1).toBool()) {
+        for(int i = 0; i < item->rowCount(); i++) {
+            paths << item->child(i)->data(Qt::UserRole).toString();
+        }
     } else {
-        QModelIndex index = this->ui->listView->selectionModel()->selectedIndexes().first();
-        auto directoryRecord = this->directoryModel->record(index.row());
-        emit this->directoryModel->directoryRescan(directoryRecord);
+        paths << item->data(Qt::UserRole).toString();
     }
+
+    return paths;
+}
+
+QModelIndex SettingsForm::directoryIndexForPath(QString path) {
+    for(int i = 0; i < this->directoryModel->rowCount(); i++) {
+        if(this->directoryModel->record(i).value("full_path").toString() == path) {
+            return this->directoryModel->index(i, 0);
+        }
+    }
+
+    return QModelIndex();
 }
@@ -6,7 +6,10 @@
 #define NODOKANATIVE_SETTINGSFORM_H
 
 #include <QtWidgets/QWidget>
+#include <QStandardItemModel>
+#include <QSet>
 #include <src/model/Directory.h>
+#include <src/core/Setting.h>
 #include "ui_SettingsForm.h"
 
 namespace Ui {
@@ -16,18 +19,35 @@ namespace Ui {
 
 class SettingsForm : public QWidget  {
 public:
-    SettingsForm(Directory* directoryModel, QWidget *parent = 0);
+    SettingsForm(Directory* directoryModel, Core::Setting* setting, QWidget *parent = 0);
     virtual ~SettingsForm();
 
 private:
     Directory *directoryModel;
+    Core::Setting* setting;
+
+    // directories grouped by the folder they are in, rebuilt from directoryModel whenever it changes
+    QStandardItemModel* directoryTreeModel;
+    QSet<QString> collapsedGroups;
+    void rebuildDirectoryTree();
+
+    // the selected directory, or every directory of the selected group
+    QStringList selectedDirectoryPaths();
+    QModelIndex directoryIndexForPath(QString path);
+
     void performAddDirectory();
     void performRemoveDirectory();
     void performClose();
     void performRescan();
+    void performToggleDirectory();
+    void performSetCollection();
+    void performSaveSpeedPresets();
+    void performSaveAccentColor();
+    void performSaveFileExtensions();
 
     Ui::SettingsForm *ui;
     void setup();
+    void setupGeneralTab();
 
 };
 